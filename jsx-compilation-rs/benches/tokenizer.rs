@@ -0,0 +1,101 @@
+//! Native counterpart of `benchmark.js`
+//!
+//! Runs the same inputs in-process, so the numbers exclude the process
+//! startup that dominates the `compare` binary timings. Two extra cases with
+//! long text and attribute runs exercise the bulk scanning path.
+//!
+//! ```bash
+//! cargo bench --bench tokenizer
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use jsx_compilation_rs::{is_valid_jsx, tokenizer};
+
+/// The cases from `benchmark.js`, in the same order
+fn cases() -> Vec<(&'static str, String)> {
+    let large = r#"
+      <div class="app">
+        <header class="header">
+          <h1 id="title">My App</h1>
+          <nav class="nav">
+            <a href="/">Home</a>
+            <a href="/about">About</a>
+            <a href="/contact">Contact</a>
+          </nav>
+        </header>
+        <main class="main">
+          <section class="hero">
+            <h2>Welcome</h2>
+            <p>This is a test paragraph with <strong>bold</strong> text.</p>
+          </section>
+          <section class="content">
+            <article class="post">
+              <h3>Post Title</h3>
+              <p>Post content goes here...</p>
+            </article>
+          </section>
+        </main>
+        <footer class="footer">
+          <p>Copyright 2024</p>
+        </footer>
+      </div>
+    "#
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    let long_text = format!(
+        "<article>{}</article>",
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(200)
+    );
+    let long_attributes = format!(
+        "<ul>{}</ul>",
+        r#"<li class="listItemWithAVeryLongClassName" key={itemIdentifier}>entry</li>"#.repeat(100)
+    );
+
+    vec![
+        ("Simple Element", "<div>Hello</div>".to_string()),
+        (
+            "Element with Attributes",
+            r#"<div class="container" id="main">Content</div>"#.to_string(),
+        ),
+        (
+            "Nested Elements",
+            "<div><p><span>Nested</span> content</p></div>".to_string(),
+        ),
+        (
+            "Complex JSX",
+            r#"<h1 id="title" name="name"><span>hello</span>world</h1>"#.to_string(),
+        ),
+        (
+            "Multiple Attributes",
+            r#"<div class="test" id="main" data-value="123" onClick={handleClick}>content</div>"#
+                .to_string(),
+        ),
+        ("Large JSX", large),
+        ("Long Text", long_text),
+        ("Long Attributes", long_attributes),
+    ]
+}
+
+fn bench_tokenizer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenizer");
+    for (name, jsx) in cases() {
+        group.throughput(Throughput::Bytes(jsx.len() as u64));
+        // Some cases are rejected, as in benchmark.js; the error path is timed too
+        group.bench_function(name, |b| b.iter(|| tokenizer(black_box(&jsx))));
+    }
+    group.finish();
+}
+
+fn bench_is_valid_jsx(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_valid_jsx");
+    for (name, jsx) in cases() {
+        group.throughput(Throughput::Bytes(jsx.len() as u64));
+        group.bench_function(name, |b| b.iter(|| is_valid_jsx(black_box(&jsx))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tokenizer, bench_is_valid_jsx);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+//! The arithmetic mini-compiler, ported from `tokenize.ts`, `toAST.ts` and `evaluate.ts`
+//!
+//! [`tokenize`] splits an expression into tokens, [`parse`] builds a tree
+//! with the same JSON shape as the TypeScript `ASTNode`, and [`evaluate`]
+//! computes its value:
+//!
+//! ```rust
+//! use jsx_compilation_rs::arithmetic::{evaluate, parse};
+//!
+//! let ast = parse("2+3*4").unwrap();
+//! assert_eq!(
+//!     serde_json::to_value(&ast).unwrap(),
+//!     serde_json::json!({
+//!         "type": "Program",
+//!         "children": [{
+//!             "type": "Additive",
+//!             "children": [
+//!                 {"type": "Numeric", "value": "2"},
+//!                 {
+//!                     "type": "Multiplicative",
+//!                     "children": [
+//!                         {"type": "Numeric", "value": "3"},
+//!                         {"type": "Numeric", "value": "4"}
+//!                     ]
+//!                 }
+//!             ]
+//!         }]
+//!     })
+//! );
+//! assert_eq!(evaluate(&ast), 14.0);
+//! ```
+//!
+//! Beyond the TypeScript version, expressions may use `-`, `/`, parentheses,
+//! unary minus and decimals, and whitespace is skipped.
+
+mod ast;
+mod evaluate;
+mod parser;
+mod tokenize;
+
+pub use ast::{ASTNode, NodeType};
+pub use evaluate::evaluate;
+pub use parser::parse;
+pub use tokenize::{tokenize, Token, TokenType};
+
+use crate::span::Span;
+use serde::{Deserialize, Serialize};
+
+/// Why an expression could not be tokenized or parsed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ArithmeticError {
+    /// A character that starts no token
+    UnexpectedCharacter { character: char, offset: usize },
+    /// A token that cannot appear at this point of the expression
+    UnexpectedToken { token_type: TokenType, span: Span },
+    /// The expression stopped where an operand or `)` was needed
+    UnexpectedEndOfInput { offset: usize },
+}
+
+impl ArithmeticError {
+    /// Where the error is in the expression
+    pub fn span(&self) -> Span {
+        match self {
+            ArithmeticError::UnexpectedCharacter { character, offset } => {
+                Span::new(*offset, offset + character.len_utf8())
+            }
+            ArithmeticError::UnexpectedToken { span, .. } => *span,
+            ArithmeticError::UnexpectedEndOfInput { offset } => Span::new(*offset, *offset),
+        }
+    }
+}
+
+impl std::fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticError::UnexpectedCharacter { character, offset } => {
+                write!(f, "Unexpected character {:?} at {}", character, offset)
+            }
+            ArithmeticError::UnexpectedToken { token_type, span } => {
+                write!(f, "Unexpected {} at {}", token_type, span.start)
+            }
+            ArithmeticError::UnexpectedEndOfInput { .. } => {
+                write!(f, "Unexpected end of expression")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Node types, named as in `NodeTypes` of `ast.ts`
+///
+/// `Subtractive`, `Divisive` and `Negative` have no TypeScript counterpart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeType {
+    /// The root; its last child is the value of the program
+    Program,
+    /// A number, kept as written in `value`
+    Numeric,
+    /// `children[0] + children[1]`
+    Additive,
+    /// `children[0] - children[1]`
+    Subtractive,
+    /// `children[0] * children[1]`
+    Multiplicative,
+    /// `children[0] / children[1]`
+    Divisive,
+    /// `-children[0]`
+    Negative,
+}
+
+/// A node of the arithmetic syntax tree, serialized like `ASTNode` in `ast.ts`
+///
+/// `value` and `children` are left out of the JSON when absent, as they are
+/// in TypeScript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ASTNode {
+    #[serde(rename = "type")]
+    pub node_type: NodeType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<ASTNode>>,
+}
+
+impl ASTNode {
+    pub fn new(node_type: NodeType) -> Self {
+        Self {
+            node_type,
+            value: None,
+            children: None,
+        }
+    }
+
+    /// A `Numeric` node
+    pub fn numeric(value: impl Into<String>) -> Self {
+        Self {
+            value: Some(value.into()),
+            ..Self::new(NodeType::Numeric)
+        }
+    }
+
+    /// A node of `node_type` over `children`
+    pub fn with_children(node_type: NodeType, children: Vec<ASTNode>) -> Self {
+        Self {
+            children: Some(children),
+            ..Self::new(node_type)
+        }
+    }
+
+    pub fn append_child(&mut self, child: ASTNode) {
+        self.children.get_or_insert_with(Vec::new).push(child);
+    }
+
+    /// The children, empty if there are none
+    pub fn children(&self) -> &[ASTNode] {
+        self.children.as_deref().unwrap_or_default()
+    }
+}
@@ -0,0 +1,31 @@
+use super::ast::{ASTNode, NodeType};
+
+/// Compute the value of a tree, like `evaluate.ts`
+///
+/// A `Program` is worth its last child, or 0 without children. Arithmetic
+/// follows JavaScript numbers: dividing by zero gives an infinity, and a
+/// missing operand or unreadable `Numeric` value gives NaN, so trees
+/// deserialized from JSON can always be evaluated.
+///
+/// ```rust
+/// use jsx_compilation_rs::arithmetic::{evaluate, parse};
+///
+/// assert_eq!(evaluate(&parse("-(1.5 + 2) * 4 / 2").unwrap()), -7.0);
+/// assert_eq!(evaluate(&parse("").unwrap()), 0.0);
+/// ```
+pub fn evaluate(node: &ASTNode) -> f64 {
+    let operand = |index: usize| node.children().get(index).map_or(f64::NAN, evaluate);
+    match node.node_type {
+        NodeType::Program => node.children().last().map_or(0.0, evaluate),
+        NodeType::Numeric => node
+            .value
+            .as_deref()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(f64::NAN),
+        NodeType::Additive => operand(0) + operand(1),
+        NodeType::Subtractive => operand(0) - operand(1),
+        NodeType::Multiplicative => operand(0) * operand(1),
+        NodeType::Divisive => operand(0) / operand(1),
+        NodeType::Negative => -operand(0),
+    }
+}
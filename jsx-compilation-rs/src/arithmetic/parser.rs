@@ -0,0 +1,157 @@
+use super::ast::{ASTNode, NodeType};
+use super::tokenize::{tokenize, Token, TokenType};
+use super::ArithmeticError;
+
+/// Recursive descent over the tokens, one method per rule
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    /// Length of the input, for errors at its end
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek_type(&self) -> Option<TokenType> {
+        self.tokens.get(self.position).map(|token| token.token_type)
+    }
+
+    fn read(&mut self) -> Result<&'a Token, ArithmeticError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .ok_or(ArithmeticError::UnexpectedEndOfInput { offset: self.end })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// `additive -> multiplicative (("+" | "-") multiplicative)*`
+    ///
+    /// See [`chain`] for how the operands nest.
+    fn additive(&mut self) -> Result<ASTNode, ArithmeticError> {
+        let first = self.multiplicative()?;
+        let mut rest = Vec::new();
+        while let Some(operator @ (TokenType::Plus | TokenType::Minus)) = self.peek_type() {
+            self.position += 1;
+            rest.push((operator, self.multiplicative()?));
+        }
+        Ok(chain(first, rest, TokenType::Plus))
+    }
+
+    /// `multiplicative -> unary (("*" | "/") unary)*`
+    ///
+    /// See [`chain`] for how the operands nest.
+    fn multiplicative(&mut self) -> Result<ASTNode, ArithmeticError> {
+        let first = self.unary()?;
+        let mut rest = Vec::new();
+        while let Some(operator @ (TokenType::Multiply | TokenType::Divide)) = self.peek_type() {
+            self.position += 1;
+            rest.push((operator, self.unary()?));
+        }
+        Ok(chain(first, rest, TokenType::Multiply))
+    }
+
+    /// `unary -> "-" unary | primary`
+    fn unary(&mut self) -> Result<ASTNode, ArithmeticError> {
+        if self.peek_type() == Some(TokenType::Minus) {
+            self.position += 1;
+            let operand = self.unary()?;
+            return Ok(ASTNode::with_children(NodeType::Negative, vec![operand]));
+        }
+        self.primary()
+    }
+
+    /// `primary -> NUMBER | "(" additive ")"`
+    fn primary(&mut self) -> Result<ASTNode, ArithmeticError> {
+        let token = self.read()?;
+        match token.token_type {
+            TokenType::Number => Ok(ASTNode::numeric(token.value.as_str())),
+            TokenType::LeftParen => {
+                let node = self.additive()?;
+                let close = self.read()?;
+                if close.token_type != TokenType::RightParen {
+                    return Err(unexpected(close));
+                }
+                Ok(node)
+            }
+            _ => Err(unexpected(token)),
+        }
+    }
+}
+
+/// Nest the operands of one precedence level
+///
+/// A chain of `nested` alone (`+` or `*`) nests to the right, as `toAST.ts`
+/// builds it, so its JSON is the same. Any other chain folds to the left as in
+/// JavaScript, so `0.1+0.2-0.3` is `(0.1+0.2)-0.3`.
+fn chain(first: ASTNode, rest: Vec<(TokenType, ASTNode)>, nested: TokenType) -> ASTNode {
+    if rest.iter().all(|(operator, _)| *operator == nested) {
+        let mut operands: Vec<ASTNode> = rest.into_iter().map(|(_, operand)| operand).collect();
+        let Some(mut node) = operands.pop() else {
+            return first;
+        };
+        for operand in operands.into_iter().rev() {
+            node = ASTNode::with_children(node_type(nested), vec![operand, node]);
+        }
+        return ASTNode::with_children(node_type(nested), vec![first, node]);
+    }
+    rest.into_iter().fold(first, |node, (operator, operand)| {
+        ASTNode::with_children(node_type(operator), vec![node, operand])
+    })
+}
+
+/// The node for a binary operator token
+fn node_type(operator: TokenType) -> NodeType {
+    match operator {
+        TokenType::Plus => NodeType::Additive,
+        TokenType::Minus => NodeType::Subtractive,
+        TokenType::Multiply => NodeType::Multiplicative,
+        _ => NodeType::Divisive,
+    }
+}
+
+fn unexpected(token: &Token) -> ArithmeticError {
+    ArithmeticError::UnexpectedToken {
+        token_type: token.token_type,
+        span: token.span,
+    }
+}
+
+/// Parse an arithmetic expression into a `Program` node, like `parser.ts`
+///
+/// `*` and `/` bind tighter than `+` and `-`. Chains of only `+` or only `*`
+/// nest to the right as in `toAST.ts`, so `1+2+3` is `1+(2+3)` and serializes
+/// the same; any other chain associates to the left as in JavaScript, so
+/// `8-2-1` is `(8-2)-1` and `8-2+3` is `(8-2)+3`. Parentheses group without a
+/// node of their own. Empty input gives a `Program` without children, as
+/// in TypeScript; anything left over after the expression is an error.
+///
+/// ```rust
+/// use jsx_compilation_rs::arithmetic::{parse, ASTNode, NodeType};
+///
+/// let ast = parse("8-2-1").unwrap();
+/// let inner = ASTNode::with_children(
+///     NodeType::Subtractive,
+///     vec![ASTNode::numeric("8"), ASTNode::numeric("2")],
+/// );
+/// assert_eq!(
+///     ast.children()[0],
+///     ASTNode::with_children(NodeType::Subtractive, vec![inner, ASTNode::numeric("1")])
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<ASTNode, ArithmeticError> {
+    let tokens = tokenize(input)?;
+    let mut program = ASTNode::new(NodeType::Program);
+    if tokens.is_empty() {
+        return Ok(program);
+    }
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+        end: input.len(),
+    };
+    program.append_child(parser.additive()?);
+    if let Some(token) = tokens.get(parser.position) {
+        return Err(unexpected(token));
+    }
+    Ok(program)
+}
@@ -0,0 +1,113 @@
+use super::ArithmeticError;
+use crate::span::Span;
+use serde::{Deserialize, Serialize};
+
+/// Token types of arithmetic expressions, named as in `tokenize.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenType {
+    Number,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    LeftParen,
+    RightParen,
+}
+
+impl std::fmt::Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TokenType::Number => "NUMBER",
+            TokenType::Plus => "PLUS",
+            TokenType::Minus => "MINUS",
+            TokenType::Multiply => "MULTIPLY",
+            TokenType::Divide => "DIVIDE",
+            TokenType::LeftParen => "LEFT_PAREN",
+            TokenType::RightParen => "RIGHT_PAREN",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A token of an arithmetic expression
+///
+/// Serializes as `{"type": "NUMBER", "value": "2"}`, like `IToken`; the span
+/// is left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    #[serde(rename = "type")]
+    pub token_type: TokenType,
+    pub value: String,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+/// Split an arithmetic expression into tokens
+///
+/// Numbers are digits with an optional fraction (`12`, `1.5`, `.5`), kept as
+/// written. Whitespace separates tokens. Unlike the regex in `tokenize.ts`,
+/// which skips anything it does not recognize, other characters are errors.
+///
+/// ```rust
+/// use jsx_compilation_rs::arithmetic::{tokenize, TokenType};
+///
+/// let tokens = tokenize("2 + 3.5").unwrap();
+/// assert_eq!(tokens[1].token_type, TokenType::Plus);
+/// assert_eq!(tokens[2].value, "3.5");
+/// assert_eq!(
+///     serde_json::to_string(&tokens[0]).unwrap(),
+///     r#"{"type":"NUMBER","value":"2"}"#
+/// );
+/// ```
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ArithmeticError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while let Some(ch) = input[offset..].chars().next() {
+        let start = offset;
+        let token_type = match ch {
+            ch if ch.is_whitespace() => {
+                offset += ch.len_utf8();
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let digits = |from: usize| {
+                    bytes[from..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count()
+                };
+                offset += digits(offset);
+                if bytes.get(offset) == Some(&b'.') {
+                    let fraction = digits(offset + 1);
+                    if fraction == 0 {
+                        // `1.` and a lone `.` are not numbers
+                        return Err(ArithmeticError::UnexpectedCharacter {
+                            character: '.',
+                            offset,
+                        });
+                    }
+                    offset += 1 + fraction;
+                }
+                TokenType::Number
+            }
+            '+' => TokenType::Plus,
+            '-' => TokenType::Minus,
+            '*' => TokenType::Multiply,
+            '/' => TokenType::Divide,
+            '(' => TokenType::LeftParen,
+            ')' => TokenType::RightParen,
+            character => return Err(ArithmeticError::UnexpectedCharacter { character, offset }),
+        };
+        if token_type != TokenType::Number {
+            offset += 1;
+        }
+        tokens.push(Token {
+            token_type,
+            value: input[start..offset].to_string(),
+            span: Span::new(start, offset),
+        });
+    }
+    Ok(tokens)
+}
@@ -0,0 +1,118 @@
+//! Syntax tree for JSX, built by [`parse`](crate::parse)
+//!
+//! Every node serializes with a `type` field naming it, as in Babel's JSX
+//! AST, and carries the byte span it was read from. Nodes the tokenizer
+//! cannot produce yet (fragments, spread attributes and expression children)
+//! are included so that the tree can describe any JSX. The JavaScript in
+//! expression containers is parsed into ESTree nodes, under [`Expression`].
+
+mod estree;
+
+pub use estree::*;
+
+use crate::span::Span;
+use serde::{Deserialize, Serialize};
+
+/// `<name attributes>children</name>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXElement {
+    pub name: String,
+    pub attributes: Vec<JSXAttributeItem>,
+    pub children: Vec<JSXChild>,
+    /// From the `<` of the opening tag to the `>` of the closing tag
+    pub span: Span,
+    /// The opening tag
+    pub opening_span: Span,
+    /// The closing tag; `None` for a self-closing element
+    pub closing_span: Option<Span>,
+}
+
+/// `<>children</>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXFragment {
+    pub children: Vec<JSXChild>,
+    pub span: Span,
+}
+
+/// `name="value"` or `name={expression}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXAttribute {
+    pub name: String,
+    /// `None` for an attribute written without a value
+    pub value: Option<JSXAttributeValue>,
+    /// From the start of the name to the end of the value
+    pub span: Span,
+}
+
+/// `{...argument}` among the attributes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXSpreadAttribute {
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// Text between tags, as written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXText {
+    pub value: String,
+    pub span: Span,
+}
+
+/// `{expression}`; the span includes the braces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXExpressionContainer {
+    /// The code between the braces
+    pub expression: Expression,
+    pub span: Span,
+}
+
+/// A quoted attribute value; the span includes the quotes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct StringLiteral {
+    pub value: String,
+    pub span: Span,
+}
+
+/// An entry in an element's attribute list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JSXAttributeItem {
+    JSXAttribute(JSXAttribute),
+    JSXSpreadAttribute(JSXSpreadAttribute),
+}
+
+/// The value of a [`JSXAttribute`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JSXAttributeValue {
+    StringLiteral(StringLiteral),
+    JSXExpressionContainer(JSXExpressionContainer),
+}
+
+/// A child of an element or fragment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JSXChild {
+    JSXElement(JSXElement),
+    JSXFragment(JSXFragment),
+    JSXText(JSXText),
+    JSXExpressionContainer(JSXExpressionContainer),
+}
+
+impl JSXChild {
+    pub fn span(&self) -> Span {
+        match self {
+            JSXChild::JSXElement(element) => element.span,
+            JSXChild::JSXFragment(fragment) => fragment.span,
+            JSXChild::JSXText(text) => text.span,
+            JSXChild::JSXExpressionContainer(container) => container.span,
+        }
+    }
+}
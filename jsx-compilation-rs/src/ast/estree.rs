@@ -0,0 +1,355 @@
+//! ESTree nodes for the JavaScript inside expression containers
+//!
+//! Field names follow the ESTree spec, except that locations are a `span`
+//! like the JSX nodes have, rather than `start`/`end` or `loc`. Optional
+//! chains set `optional` on the member or call instead of being wrapped in
+//! a `ChainExpression`.
+//!
+//! Every node writes its own `type` field, so the enums serialize their
+//! variants as they are and deserialize by dispatching on `type`.
+
+use super::JSXElement;
+use crate::span::Span;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Read the `type` of a node and return it with the node, for the enums to dispatch on
+fn node_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(String, Value), D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    let node_type = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| D::Error::missing_field("type"))?
+        .to_string();
+    Ok((node_type, value))
+}
+
+/// Deserialize the node in `value` as the variant its `type` named
+fn node<T: DeserializeOwned, E: Error>(value: Value) -> Result<T, E> {
+    serde_json::from_value(value).map_err(E::custom)
+}
+
+/// An expression, as parsed by [`parse_expression`](crate::parse_expression)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Expression {
+    Identifier(Identifier),
+    ThisExpression(ThisExpression),
+    Literal(Literal),
+    TemplateLiteral(TemplateLiteral),
+    ArrayExpression(ArrayExpression),
+    ObjectExpression(ObjectExpression),
+    MemberExpression(MemberExpression),
+    CallExpression(CallExpression),
+    ArrowFunctionExpression(ArrowFunctionExpression),
+    ConditionalExpression(ConditionalExpression),
+    LogicalExpression(LogicalExpression),
+    BinaryExpression(BinaryExpression),
+    UnaryExpression(UnaryExpression),
+    /// `...argument`; only found in array elements and call arguments
+    SpreadElement(SpreadElement),
+    JSXElement(Box<JSXElement>),
+    /// The inside of `{}`
+    JSXEmptyExpression(JSXEmptyExpression),
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(node) => node.span,
+            Expression::ThisExpression(node) => node.span,
+            Expression::Literal(node) => node.span,
+            Expression::TemplateLiteral(node) => node.span,
+            Expression::ArrayExpression(node) => node.span,
+            Expression::ObjectExpression(node) => node.span,
+            Expression::MemberExpression(node) => node.span,
+            Expression::CallExpression(node) => node.span,
+            Expression::ArrowFunctionExpression(node) => node.span,
+            Expression::ConditionalExpression(node) => node.span,
+            Expression::LogicalExpression(node) => node.span,
+            Expression::BinaryExpression(node) => node.span,
+            Expression::UnaryExpression(node) => node.span,
+            Expression::SpreadElement(node) => node.span,
+            Expression::JSXElement(node) => node.span,
+            Expression::JSXEmptyExpression(node) => node.span,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const VARIANTS: &[&str] = &[
+            "Identifier",
+            "ThisExpression",
+            "Literal",
+            "TemplateLiteral",
+            "ArrayExpression",
+            "ObjectExpression",
+            "MemberExpression",
+            "CallExpression",
+            "ArrowFunctionExpression",
+            "ConditionalExpression",
+            "LogicalExpression",
+            "BinaryExpression",
+            "UnaryExpression",
+            "SpreadElement",
+            "JSXElement",
+            "JSXEmptyExpression",
+        ];
+        let (node_type, value) = node_type(deserializer)?;
+        Ok(match node_type.as_str() {
+            "Identifier" => Expression::Identifier(node(value)?),
+            "ThisExpression" => Expression::ThisExpression(node(value)?),
+            "Literal" => Expression::Literal(node(value)?),
+            "TemplateLiteral" => Expression::TemplateLiteral(node(value)?),
+            "ArrayExpression" => Expression::ArrayExpression(node(value)?),
+            "ObjectExpression" => Expression::ObjectExpression(node(value)?),
+            "MemberExpression" => Expression::MemberExpression(node(value)?),
+            "CallExpression" => Expression::CallExpression(node(value)?),
+            "ArrowFunctionExpression" => Expression::ArrowFunctionExpression(node(value)?),
+            "ConditionalExpression" => Expression::ConditionalExpression(node(value)?),
+            "LogicalExpression" => Expression::LogicalExpression(node(value)?),
+            "BinaryExpression" => Expression::BinaryExpression(node(value)?),
+            "UnaryExpression" => Expression::UnaryExpression(node(value)?),
+            "SpreadElement" => Expression::SpreadElement(node(value)?),
+            "JSXElement" => Expression::JSXElement(node(value)?),
+            "JSXEmptyExpression" => Expression::JSXEmptyExpression(node(value)?),
+            other => return Err(D::Error::unknown_variant(other, VARIANTS)),
+        })
+    }
+}
+
+/// `name`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct Identifier {
+    pub name: String,
+    pub span: Span,
+}
+
+/// `this`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct ThisExpression {
+    pub span: Span,
+}
+
+/// The value of a [`Literal`]; serializes as the bare JSON value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LiteralValue {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+}
+
+/// `null`, `true`, `1.5` or `"text"`
+///
+/// JSON has no infinite numbers, so a literal such as `1e400` writes its value
+/// as `null`; reading it back recovers the value from `raw`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "RawLiteral")]
+pub struct Literal {
+    pub value: LiteralValue,
+    /// The literal as written, quotes included
+    pub raw: String,
+    pub span: Span,
+}
+
+/// A [`Literal`] as written in JSON, before its value is checked against `raw`
+#[derive(Deserialize)]
+struct RawLiteral {
+    value: LiteralValue,
+    raw: String,
+    span: Span,
+}
+
+impl TryFrom<RawLiteral> for Literal {
+    type Error = String;
+
+    fn try_from(literal: RawLiteral) -> Result<Self, Self::Error> {
+        let value = match literal.value {
+            LiteralValue::Null if literal.raw != "null" => {
+                match crate::expression::number_value(&literal.raw) {
+                    Some(number) => LiteralValue::Number(number),
+                    None => return Err(format!("null value for literal {}", literal.raw)),
+                }
+            }
+            value => value,
+        };
+        Ok(Literal {
+            value,
+            raw: literal.raw,
+            span: literal.span,
+        })
+    }
+}
+
+/// The text of a [`TemplateElement`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateElementValue {
+    pub raw: String,
+    /// `raw` with escapes decoded
+    pub cooked: String,
+}
+
+/// A text part of a template literal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct TemplateElement {
+    pub value: TemplateElementValue,
+    /// Whether this is the last part
+    pub tail: bool,
+    pub span: Span,
+}
+
+/// `` `text ${expression} text` ``; `quasis` has one more entry than `expressions`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct TemplateLiteral {
+    pub quasis: Vec<TemplateElement>,
+    pub expressions: Vec<Expression>,
+    pub span: Span,
+}
+
+/// `[a, , ...b]`; holes are `None`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct ArrayExpression {
+    pub elements: Vec<Option<Expression>>,
+    pub span: Span,
+}
+
+/// `key: value`, `[key]: value` or shorthand `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct Property {
+    pub key: Expression,
+    pub value: Expression,
+    pub computed: bool,
+    pub shorthand: bool,
+    pub span: Span,
+}
+
+/// An entry of an object literal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ObjectMember {
+    Property(Property),
+    SpreadElement(SpreadElement),
+}
+
+impl<'de> Deserialize<'de> for ObjectMember {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (node_type, value) = node_type(deserializer)?;
+        Ok(match node_type.as_str() {
+            "Property" => ObjectMember::Property(node(value)?),
+            "SpreadElement" => ObjectMember::SpreadElement(node(value)?),
+            other => {
+                return Err(D::Error::unknown_variant(
+                    other,
+                    &["Property", "SpreadElement"],
+                ))
+            }
+        })
+    }
+}
+
+/// `{ a: 1, b, ...c }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct ObjectExpression {
+    pub properties: Vec<ObjectMember>,
+    pub span: Span,
+}
+
+/// `object.property`, `object[property]` or `object?.property`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct MemberExpression {
+    pub object: Box<Expression>,
+    pub property: Box<Expression>,
+    /// Whether the property is in brackets
+    pub computed: bool,
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// `callee(arguments)` or `callee?.(arguments)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct CallExpression {
+    pub callee: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// `(params) => body`, with an expression body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct ArrowFunctionExpression {
+    pub params: Vec<Identifier>,
+    pub body: Box<Expression>,
+    /// Always `true`: block bodies are not supported
+    pub expression: bool,
+    pub span: Span,
+}
+
+/// `test ? consequent : alternate`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct ConditionalExpression {
+    pub test: Box<Expression>,
+    pub consequent: Box<Expression>,
+    pub alternate: Box<Expression>,
+    pub span: Span,
+}
+
+/// `left && right`, `left || right` or `left ?? right`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct LogicalExpression {
+    pub operator: String,
+    pub left: Box<Expression>,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+/// `left + right`, `left === right`, `left instanceof right`, ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct BinaryExpression {
+    pub operator: String,
+    pub left: Box<Expression>,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+/// `!argument`, `-argument`, `typeof argument`, ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct UnaryExpression {
+    pub operator: String,
+    /// Always `true`; ESTree keeps the field for postfix operators
+    pub prefix: bool,
+    pub argument: Box<Expression>,
+    pub span: Span,
+}
+
+/// `...argument`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct SpreadElement {
+    pub argument: Box<Expression>,
+    pub span: Span,
+}
+
+/// Nothing, as in `{}` or `{ }`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub struct JSXEmptyExpression {
+    pub span: Span,
+}
@@ -0,0 +1,225 @@
+use crate::diagnostic::Diagnostic;
+use crate::span::SpannedToken;
+use crate::tokenizer::tokenize_spanned;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Where a batch input's source comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchSource {
+    Text(String),
+    /// Read by the worker that tokenizes it
+    File(PathBuf),
+}
+
+/// A named input to [`tokenize_batch`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInput {
+    pub name: String,
+    pub source: BatchSource,
+}
+
+impl BatchInput {
+    pub fn text(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: BatchSource::Text(text.into()),
+        }
+    }
+
+    /// A file input, named by its path
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            name: path.display().to_string(),
+            source: BatchSource::File(path),
+        }
+    }
+}
+
+/// Why one input of a batch failed
+#[derive(Debug)]
+pub enum BatchError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The source is not valid JSX
+    Syntax(Diagnostic),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Io(e) => write!(f, "I/O error: {}", e),
+            BatchError::Syntax(diagnostic) => write!(f, "{}", diagnostic),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Io(e) => Some(e),
+            BatchError::Syntax(diagnostic) => Some(diagnostic),
+        }
+    }
+}
+
+/// The outcome for one input, in input order
+#[derive(Debug)]
+pub struct BatchResult {
+    pub name: String,
+    pub tokens: Result<Vec<SpannedToken>, BatchError>,
+    /// Time spent reading and tokenizing this input
+    pub elapsed: Duration,
+}
+
+/// Totals over a whole batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchStats {
+    pub inputs: usize,
+    /// Tokens produced by the inputs that succeeded
+    pub tokens: usize,
+    pub failures: usize,
+    /// Bytes of source tokenized, including inputs that failed
+    pub bytes: usize,
+    /// Wall-clock time for the whole batch
+    pub elapsed: Duration,
+}
+
+/// Everything [`tokenize_batch`] produced
+#[derive(Debug)]
+pub struct BatchReport {
+    pub results: Vec<BatchResult>,
+    pub stats: BatchStats,
+}
+
+impl BatchReport {
+    /// The results that failed
+    pub fn failures(&self) -> impl Iterator<Item = &BatchResult> {
+        self.results.iter().filter(|result| result.tokens.is_err())
+    }
+}
+
+/// How a batch is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchOptions {
+    /// Number of worker threads; `0` uses the available parallelism
+    pub threads: usize,
+}
+
+impl BatchOptions {
+    fn worker_count(&self, inputs: usize) -> usize {
+        let threads = match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        threads.min(inputs).max(1)
+    }
+}
+
+/// Tokenize one input, returning its token count and byte length alongside the result
+fn run_one(input: &BatchInput) -> (BatchResult, usize) {
+    let start = Instant::now();
+    let (tokens, bytes) = match &input.source {
+        BatchSource::Text(text) => (
+            tokenize_spanned(text).map_err(BatchError::Syntax),
+            text.len(),
+        ),
+        BatchSource::File(path) => match std::fs::read_to_string(path) {
+            Ok(text) => (
+                tokenize_spanned(&text).map_err(BatchError::Syntax),
+                text.len(),
+            ),
+            Err(e) => (Err(BatchError::Io(e)), 0),
+        },
+    };
+    let result = BatchResult {
+        name: input.name.clone(),
+        tokens,
+        elapsed: start.elapsed(),
+    };
+    (result, bytes)
+}
+
+/// Tokenize many inputs on a pool of worker threads
+///
+/// Workers take the next unclaimed input until none are left, so a few large
+/// files do not hold up the rest. Results come back in input order, and a
+/// failing input does not stop the others.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_batch, BatchInput, BatchOptions};
+///
+/// let inputs = vec![
+///     BatchInput::text("ok.jsx", "<p>hello</p>"),
+///     BatchInput::text("bad.jsx", "<p class=x>hello</p>"),
+/// ];
+/// let report = tokenize_batch(inputs, BatchOptions::default());
+///
+/// assert_eq!(report.results[0].name, "ok.jsx");
+/// assert_eq!(report.stats.tokens, 8);
+/// assert_eq!(report.stats.failures, 1);
+/// ```
+pub fn tokenize_batch(
+    inputs: impl IntoIterator<Item = BatchInput>,
+    options: BatchOptions,
+) -> BatchReport {
+    let start = Instant::now();
+    let inputs: Vec<BatchInput> = inputs.into_iter().collect();
+    let workers = options.worker_count(inputs.len());
+
+    let mut done: Vec<(usize, BatchResult, usize)> = if workers == 1 {
+        inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let (result, bytes) = run_one(input);
+                (index, result, bytes)
+            })
+            .collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(input) = inputs.get(index) else {
+                                break done;
+                            };
+                            let (result, bytes) = run_one(input);
+                            done.push((index, result, bytes));
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("batch worker panicked"))
+                .collect()
+        })
+    };
+    done.sort_unstable_by_key(|(index, _, _)| *index);
+
+    let mut stats = BatchStats {
+        inputs: inputs.len(),
+        ..BatchStats::default()
+    };
+    let results = done
+        .into_iter()
+        .map(|(_, result, bytes)| {
+            stats.bytes += bytes;
+            match &result.tokens {
+                Ok(tokens) => stats.tokens += tokens.len(),
+                Err(_) => stats.failures += 1,
+            }
+            result
+        })
+        .collect();
+    stats.elapsed = start.elapsed();
+
+    BatchReport { results, stats }
+}
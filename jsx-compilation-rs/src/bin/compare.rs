@@ -1,26 +1,71 @@
-use jsx_compilation_rs::{tokenizer, tokenize_to_json};
+use jsx_compilation_rs::{fix, tokenize_spanned, FrameOptions, Locale};
 use std::env;
+use std::io::IsTerminal;
+
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} [--locale <en|zh-CN>] [--fix] <jsx_string>",
+        program
+    );
+    std::process::exit(1);
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    
-    if args.len() != 2 {
-        eprintln!("Usage: {} <jsx_string>", args[0]);
-        std::process::exit(1);
+
+    let mut locale = Locale::default();
+    let mut apply_fixes = false;
+    let mut jsx = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        if arg == "--locale" {
+            let Some(tag) = rest.next() else {
+                usage(&args[0])
+            };
+            locale = match tag.parse() {
+                Ok(locale) => locale,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+        } else if arg == "--fix" {
+            apply_fixes = true;
+        } else if jsx.is_none() {
+            jsx = Some(arg);
+        } else {
+            usage(&args[0]);
+        }
     }
-    
-    let jsx = &args[1];
-    
-    match tokenizer(jsx) {
+    let Some(jsx) = jsx else { usage(&args[0]) };
+
+    let fixed;
+    let jsx = if apply_fixes {
+        fixed = fix(jsx);
+        println!("FIXED:{}", fixed);
+        &fixed
+    } else {
+        jsx
+    };
+
+    match tokenize_spanned(jsx) {
         Ok(tokens) => {
             // Print in a format that's easy to compare
             println!("TOKENS_COUNT:{}", tokens.len());
-            for (i, token) in tokens.iter().enumerate() {
+            for (i, spanned) in tokens.iter().enumerate() {
+                let token = &spanned.token;
                 println!("TOKEN_{}:{}:{}", i, token.token_type, token.value);
             }
         }
-        Err(e) => {
-            println!("ERROR:{}", e);
+        Err(diagnostic) => {
+            println!("ERROR:{}", diagnostic.error.localized(locale));
+            println!("ERROR_CODE:{}", diagnostic.code());
+            // Human-readable code frame on stderr, colored when attached to a terminal
+            let options = FrameOptions {
+                color: std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+                ..FrameOptions::default()
+            };
+            eprint!("{}", diagnostic.render(jsx, locale, options));
             std::process::exit(1);
         }
     }
@@ -0,0 +1,138 @@
+use crate::diagnostic::Diagnostic;
+use crate::tokenizer::{Checkpoint, Token, Tokenizer};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bytes lexed between checks of the budget and the cancellation flag
+const CHECK_INTERVAL: usize = 4096;
+
+/// How much work one call to [`tokenize_budgeted`] or [`Paused::resume`] may do
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget<'a> {
+    /// Steps to take before pausing, one per byte of input; `None` for no limit
+    pub steps: Option<usize>,
+    /// Pause once this is set; checked every few thousand bytes
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a> Budget<'a> {
+    /// Pause after `steps` bytes
+    pub fn steps(steps: usize) -> Self {
+        Self {
+            steps: Some(steps),
+            cancel: None,
+        }
+    }
+
+    /// Pause once `cancel` is set
+    pub fn cancel(cancel: &'a AtomicBool) -> Self {
+        Self {
+            steps: None,
+            cancel: Some(cancel),
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+}
+
+/// Outcome of a budgeted run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Progress {
+    /// The whole input was lexed; the tokens are those of [`tokenizer`](crate::tokenizer)
+    Done(Vec<Token>),
+    /// The budget ran out or the run was cancelled first
+    Paused(Paused),
+}
+
+/// A budgeted run that stopped early, holding everything needed to continue it
+///
+/// Serializable, so the work can be carried across frames or stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Paused {
+    tokens: Vec<Token>,
+    checkpoint: Checkpoint,
+}
+
+impl Paused {
+    /// The tokens completed so far
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Byte offset lexing will continue from
+    pub fn offset(&self) -> usize {
+        self.checkpoint.offset()
+    }
+
+    /// Continue lexing `input`, which must be the input the run started on
+    pub fn resume(self, input: &str, budget: Budget<'_>) -> Result<Progress, Diagnostic> {
+        run(
+            Tokenizer::from_checkpoint(self.checkpoint),
+            self.tokens,
+            input,
+            budget,
+        )
+    }
+}
+
+/// Tokenize JSX, giving up control once `budget` is spent
+///
+/// Lexing proceeds in slices of a few thousand bytes, checking the budget
+/// and the cancellation flag between them, so a caller on a UI thread can
+/// stop a huge input and resume it later with [`Paused::resume`]. Errors are
+/// reported as with [`tokenize_spanned`](crate::tokenize_spanned).
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_budgeted, tokenizer, Budget, Progress};
+///
+/// let source = format!("<ul>{}</ul>", "<li>item</li>".repeat(1000));
+/// let mut progress = tokenize_budgeted(&source, Budget::steps(5000)).unwrap();
+/// let mut pauses = 0;
+/// let tokens = loop {
+///     match progress {
+///         Progress::Done(tokens) => break tokens,
+///         Progress::Paused(paused) => {
+///             pauses += 1;
+///             progress = paused.resume(&source, Budget::steps(5000)).unwrap();
+///         }
+///     }
+/// };
+/// assert_eq!(pauses, 2);
+/// assert_eq!(tokens, tokenizer(&source).unwrap());
+/// ```
+pub fn tokenize_budgeted(input: &str, budget: Budget<'_>) -> Result<Progress, Diagnostic> {
+    run(Tokenizer::new(), Vec::new(), input, budget)
+}
+
+fn run(
+    mut tokenizer: Tokenizer,
+    mut tokens: Vec<Token>,
+    input: &str,
+    budget: Budget<'_>,
+) -> Result<Progress, Diagnostic> {
+    let mut remaining = budget.steps.unwrap_or(usize::MAX);
+    loop {
+        let offset = tokenizer.offset();
+        if offset == input.len() {
+            return Ok(Progress::Done(tokens));
+        }
+        if remaining == 0 || budget.cancelled() {
+            return Ok(Progress::Paused(Paused {
+                tokens,
+                checkpoint: tokenizer.checkpoint(),
+            }));
+        }
+        let mut end = offset + remaining.min(CHECK_INTERVAL).min(input.len() - offset);
+        while !input.is_char_boundary(end) {
+            end += 1;
+        }
+        tokenizer
+            .process_str(&input[offset..end])
+            .map_err(|error| tokenizer.diagnose(input, error))?;
+        tokens.extend(tokenizer.drain_tokens());
+        remaining = remaining.saturating_sub(end - offset);
+    }
+}
@@ -0,0 +1,594 @@
+//! The tokenizer's state machine, declared as data
+//!
+//! [`SPEC`] lists the transitions as rules: from which [`State`]s, on which
+//! [`CharClass`]es, the [`Op`]s to perform and the state to move to. At
+//! compile time the rules are expanded into a dense `State × CharClass` table
+//! that the [`Tokenizer`](crate::tokenizer::Tokenizer) drives, as do
+//! [`validate`](crate::validate) and the other tokenizers that only slice the
+//! input. Two rules covering the same cell fail
+//! the build; [`check`] also reports states the rules never reach.
+//!
+//! A character with no transition is an error: `InvalidFirstCharacter` in
+//! `Start`, `UnexpectedCharacter` anywhere else.
+
+use crate::span::Span;
+use crate::tokenizer::{State, TokenType, TokenizerError};
+use std::ops::ControlFlow;
+
+/// The characters the state machine tells apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharClass {
+    /// `<`
+    Lt,
+    /// `>`
+    Gt,
+    /// `/`
+    Slash,
+    /// `=`
+    Eq,
+    /// `"`
+    Quote,
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// A single space
+    Space,
+    /// ASCII letters and digits
+    Alnum,
+    /// Anything else, including all non-ASCII characters
+    Other,
+}
+
+const CLASS_COUNT: usize = 10;
+const STATE_COUNT: usize = 12;
+
+impl CharClass {
+    pub const ALL: [CharClass; CLASS_COUNT] = [
+        CharClass::Lt,
+        CharClass::Gt,
+        CharClass::Slash,
+        CharClass::Eq,
+        CharClass::Quote,
+        CharClass::LBrace,
+        CharClass::RBrace,
+        CharClass::Space,
+        CharClass::Alnum,
+        CharClass::Other,
+    ];
+
+    /// Class of an ASCII byte; bytes of multi-byte characters are `Other`
+    pub const fn of_byte(byte: u8) -> CharClass {
+        match byte {
+            b'<' => CharClass::Lt,
+            b'>' => CharClass::Gt,
+            b'/' => CharClass::Slash,
+            b'=' => CharClass::Eq,
+            b'"' => CharClass::Quote,
+            b'{' => CharClass::LBrace,
+            b'}' => CharClass::RBrace,
+            b' ' => CharClass::Space,
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => CharClass::Alnum,
+            _ => CharClass::Other,
+        }
+    }
+
+    #[inline]
+    pub fn of(ch: char) -> CharClass {
+        if ch.is_ascii() {
+            ASCII_CLASSES[ch as usize]
+        } else {
+            CharClass::Other
+        }
+    }
+}
+
+/// [`CharClass::of_byte`] for every ASCII byte, so classifying is one load
+static ASCII_CLASSES: [CharClass; 128] = {
+    let mut classes = [CharClass::Other; 128];
+    let mut byte = 0;
+    while byte < 128 {
+        classes[byte] = CharClass::of_byte(byte as u8);
+        byte += 1;
+    }
+    classes
+};
+
+impl State {
+    pub const ALL: [State; STATE_COUNT] = [
+        State::Start,
+        State::FoundLeftParentheses,
+        State::JSXIdentifier,
+        State::Attribute,
+        State::AttributeKey,
+        State::AttributeValue,
+        State::AttributeStringValue,
+        State::AttributeExpressionValue,
+        State::TryLeaveAttribute,
+        State::FoundRightParentheses,
+        State::JSXText,
+        State::SelfClosingSlash,
+    ];
+}
+
+/// A step performed on a transition, in the order listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Start a token of this type with the current character
+    Begin(TokenType),
+    /// Start an empty token of this type at the current character, e.g. at an opening quote
+    Open(TokenType),
+    /// Append the current character to the token
+    Push,
+    /// Emit the token, ending before the current character
+    Flush,
+    /// Emit the token, ending after the current character, e.g. at a closing quote
+    FlushThrough,
+    /// Emit the token with an empty span at the current character
+    FlushEmpty,
+    /// Emit the current character as a token of this type
+    Punct(TokenType),
+    /// Mark the tag being read as a closing tag
+    ClosingTag,
+    /// Emit the current `>` as the end of a tag, tracking nesting depth
+    TagEnd,
+}
+
+/// One line of the transition specification
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub from: &'static [State],
+    pub on: &'static [CharClass],
+    /// Only active when self-closing tags are enabled
+    pub self_closing: bool,
+    pub ops: &'static [Op],
+    pub to: State,
+}
+
+const fn rule(
+    from: &'static [State],
+    on: &'static [CharClass],
+    ops: &'static [Op],
+    to: State,
+) -> Rule {
+    Rule {
+        from,
+        on,
+        self_closing: false,
+        ops,
+        to,
+    }
+}
+
+const fn self_closing_rule(
+    from: &'static [State],
+    on: &'static [CharClass],
+    ops: &'static [Op],
+    to: State,
+) -> Rule {
+    Rule {
+        self_closing: true,
+        ..rule(from, on, ops, to)
+    }
+}
+
+/// The transitions of the JSX tokenizer
+#[rustfmt::skip]
+pub const SPEC: &[Rule] = {
+    use CharClass::*;
+    use State::*;
+    use TokenType::{AttributeExpressionValue as ExprValue, AttributeStringValue as StringValue};
+
+    /// Every class that continues text
+    const TEXT: &[CharClass] = &[Gt, Slash, Eq, Quote, LBrace, RBrace, Space, Alnum, Other];
+
+    &[
+        rule(&[Start], &[Lt], &[Op::Punct(TokenType::LeftParentheses)], FoundLeftParentheses),
+        // Tag names
+        rule(&[FoundLeftParentheses], &[Alnum], &[Op::Begin(TokenType::JSXIdentifier)], JSXIdentifier),
+        rule(&[FoundLeftParentheses], &[Slash], &[Op::Punct(TokenType::BackSlash), Op::ClosingTag], FoundLeftParentheses),
+        rule(&[JSXIdentifier], &[Alnum], &[Op::Push], JSXIdentifier),
+        rule(&[JSXIdentifier], &[Space], &[Op::Flush], Attribute),
+        rule(&[JSXIdentifier], &[Gt], &[Op::Flush, Op::TagEnd], FoundRightParentheses),
+        // Attributes
+        rule(&[Attribute], &[Alnum], &[Op::Begin(TokenType::AttributeKey)], AttributeKey),
+        rule(&[Attribute], &[Eq], &[Op::FlushEmpty], AttributeValue),
+        rule(&[AttributeKey], &[Alnum], &[Op::Push], AttributeKey),
+        rule(&[AttributeKey], &[Eq], &[Op::Flush], AttributeValue),
+        rule(&[AttributeValue], &[Quote], &[Op::Open(StringValue)], AttributeStringValue),
+        rule(&[AttributeValue], &[LBrace], &[Op::Open(ExprValue)], AttributeExpressionValue),
+        rule(&[AttributeStringValue], &[Alnum], &[Op::Push], AttributeStringValue),
+        rule(&[AttributeStringValue], &[Quote], &[Op::FlushThrough], TryLeaveAttribute),
+        rule(&[AttributeExpressionValue], &[Alnum], &[Op::Push], AttributeExpressionValue),
+        rule(&[AttributeExpressionValue], &[RBrace], &[Op::FlushThrough], TryLeaveAttribute),
+        rule(&[TryLeaveAttribute], &[Space], &[], Attribute),
+        rule(&[TryLeaveAttribute], &[Gt], &[Op::TagEnd], FoundRightParentheses),
+        // Self-closing tags
+        self_closing_rule(&[JSXIdentifier], &[Slash], &[Op::Flush, Op::Punct(TokenType::BackSlash)], SelfClosingSlash),
+        self_closing_rule(&[Attribute, TryLeaveAttribute], &[Slash], &[Op::Punct(TokenType::BackSlash)], SelfClosingSlash),
+        self_closing_rule(&[SelfClosingSlash], &[Gt], &[Op::Punct(TokenType::RightParentheses)], FoundRightParentheses),
+        // Text
+        rule(&[FoundRightParentheses], &[Lt], &[Op::Punct(TokenType::LeftParentheses)], FoundLeftParentheses),
+        rule(&[FoundRightParentheses], TEXT, &[Op::Begin(TokenType::JSXText)], JSXText),
+        rule(&[JSXText], &[Lt], &[Op::Flush, Op::Punct(TokenType::LeftParentheses)], FoundLeftParentheses),
+        rule(&[JSXText], TEXT, &[Op::Push], JSXText),
+    ]
+};
+
+/// How a transition ends the in-progress token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Flush {
+    None,
+    Before,
+    Through,
+    Empty,
+}
+
+/// What a transition does to the in-progress token after flushing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Collect {
+    None,
+    Begin(TokenType),
+    Open(TokenType),
+    Push,
+}
+
+/// Which single-character token a transition emits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Emit {
+    None,
+    Punct(TokenType),
+    TagEnd,
+}
+
+/// A table cell: a rule's ops compiled into fixed slots, and where to go
+///
+/// The slots run in order, so a rule must list its ops as flush, then
+/// collect, then emit, then `ClosingTag`, each at most once.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Transition {
+    pub(crate) flush: Flush,
+    pub(crate) collect: Collect,
+    pub(crate) emit: Emit,
+    pub(crate) closing_tag: bool,
+    pub(crate) next: State,
+}
+
+impl Transition {
+    const fn compile(ops: &[Op], next: State) -> Transition {
+        let mut transition = Transition {
+            flush: Flush::None,
+            collect: Collect::None,
+            emit: Emit::None,
+            closing_tag: false,
+            next,
+        };
+        // Slot of the last op placed: 0 flush, 1 collect, 2 emit, 3 closing tag
+        let mut last = -1;
+        let mut i = 0;
+        while i < ops.len() {
+            let slot = match ops[i] {
+                Op::Flush => {
+                    transition.flush = Flush::Before;
+                    0
+                }
+                Op::FlushThrough => {
+                    transition.flush = Flush::Through;
+                    0
+                }
+                Op::FlushEmpty => {
+                    transition.flush = Flush::Empty;
+                    0
+                }
+                Op::Begin(token_type) => {
+                    transition.collect = Collect::Begin(token_type);
+                    1
+                }
+                Op::Open(token_type) => {
+                    transition.collect = Collect::Open(token_type);
+                    1
+                }
+                Op::Push => {
+                    transition.collect = Collect::Push;
+                    1
+                }
+                Op::Punct(token_type) => {
+                    transition.emit = Emit::Punct(token_type);
+                    2
+                }
+                Op::TagEnd => {
+                    transition.emit = Emit::TagEnd;
+                    2
+                }
+                Op::ClosingTag => {
+                    transition.closing_tag = true;
+                    3
+                }
+            };
+            if slot <= last {
+                panic!(
+                    "ops must be listed as flush, collect, emit, closing tag, each at most once"
+                );
+            }
+            last = slot;
+            i += 1;
+        }
+        transition
+    }
+
+    /// Whether the transition only appends the character and stays in `state`
+    const fn is_run(&self, state: State) -> bool {
+        self.next as usize == state as usize
+            && matches!(self.flush, Flush::None)
+            && matches!(self.collect, Collect::Push)
+            && matches!(self.emit, Emit::None)
+            && !self.closing_tag
+    }
+}
+
+/// How a run of bytes that keeps a state unchanged is found
+#[derive(Debug, Clone, Copy)]
+enum Scan {
+    /// The state has no self-loop that only appends
+    None,
+    /// Every byte but this one continues the run
+    Until(u8),
+    /// Bytes whose bit is set continue the run; non-ASCII bytes continue it if `non_ascii`
+    While { ascii: u128, non_ascii: bool },
+}
+
+/// A token found by [`Table::drive`], by position in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Emitted {
+    /// The in-progress token ended; `value` leaves out its delimiters
+    Token {
+        token_type: TokenType,
+        span: Span,
+        value: Span,
+    },
+    /// An attribute without a key, for which the tokenizer emits an empty token
+    Empty { offset: usize },
+    /// A single-character token; `>` closing a tag is a `RightParentheses`
+    Punct { token_type: TokenType, span: Span },
+}
+
+/// The expanded transition table
+pub(crate) struct Table {
+    cells: [[Option<Transition>; CLASS_COUNT]; STATE_COUNT],
+    scans: [Scan; STATE_COUNT],
+}
+
+/// Whether `transition` appends the character and stays in `state`
+const fn is_run(transition: Option<Transition>, state: State) -> bool {
+    match transition {
+        Some(transition) => transition.is_run(state),
+        None => false,
+    }
+}
+
+impl Table {
+    /// Expand `spec`, panicking (at compile time for the built-in tables) on ambiguity
+    const fn build(spec: &'static [Rule], self_closing: bool) -> Table {
+        let mut cells = [[None; CLASS_COUNT]; STATE_COUNT];
+        let mut i = 0;
+        while i < spec.len() {
+            let rule = spec[i];
+            if self_closing || !rule.self_closing {
+                let mut f = 0;
+                while f < rule.from.len() {
+                    let mut c = 0;
+                    while c < rule.on.len() {
+                        let (state, class) = (rule.from[f] as usize, rule.on[c] as usize);
+                        if cells[state][class].is_some() {
+                            panic!(
+                                "two rules in the transition spec cover the same state and class"
+                            );
+                        }
+                        cells[state][class] = Some(Transition::compile(rule.ops, rule.to));
+                        c += 1;
+                    }
+                    f += 1;
+                }
+            }
+            i += 1;
+        }
+
+        let mut scans = [Scan::None; STATE_COUNT];
+        let mut s = 0;
+        while s < STATE_COUNT {
+            let state = State::ALL[s];
+            let mut ascii = 0u128;
+            let mut byte = 0;
+            while byte < 128 {
+                if is_run(cells[s][CharClass::of_byte(byte as u8) as usize], state) {
+                    ascii |= 1 << byte;
+                }
+                byte += 1;
+            }
+            let non_ascii = is_run(cells[s][CharClass::Other as usize], state);
+            scans[s] = if ascii == 0 {
+                Scan::None
+            } else if non_ascii && ascii.count_ones() == 127 {
+                Scan::Until((!ascii).trailing_zeros() as u8)
+            } else {
+                Scan::While { ascii, non_ascii }
+            };
+            s += 1;
+        }
+
+        Table { cells, scans }
+    }
+
+    /// The table for the default mode or with self-closing tags enabled
+    pub(crate) fn get(self_closing: bool) -> &'static Table {
+        if self_closing {
+            &SELF_CLOSING
+        } else {
+            &DEFAULT
+        }
+    }
+
+    /// Look up the transition out of `state` on `ch`
+    #[inline]
+    pub(crate) fn transition(&self, state: State, ch: char) -> Result<Transition, TokenizerError> {
+        match self.cells[state as usize][CharClass::of(ch) as usize] {
+            Some(transition) => Ok(transition),
+            None if state == State::Start => Err(TokenizerError::InvalidFirstCharacter),
+            None => Err(TokenizerError::UnexpectedCharacter(ch)),
+        }
+    }
+
+    /// Length of the prefix of `bytes` that only appends to the token in `state`
+    ///
+    /// Runs end on an ASCII delimiter, so the length is always a character boundary.
+    #[inline]
+    pub(crate) fn run_len(&self, state: State, bytes: &[u8]) -> usize {
+        match self.scans[state as usize] {
+            Scan::None => 0,
+            Scan::Until(stop) => memchr::memchr(stop, bytes).unwrap_or(bytes.len()),
+            Scan::While { ascii, non_ascii } => bytes
+                .iter()
+                .position(|&b| {
+                    if b < 128 {
+                        ascii & (1 << b) == 0
+                    } else {
+                        !non_ascii
+                    }
+                })
+                .unwrap_or(bytes.len()),
+        }
+    }
+
+    /// Run the state machine over `input`, passing each token to `sink` in order
+    ///
+    /// Keeps no token values, only where the token being read starts, so the
+    /// tokens are slices of `input` and runs are skipped in bulk. Returns
+    /// `Break` with the offset after the character whose token `sink` stopped
+    /// at, and the error with its byte offset if `input` is rejected.
+    pub(crate) fn drive(
+        &self,
+        input: &str,
+        mut sink: impl FnMut(Emitted) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<usize>, (TokenizerError, usize)> {
+        // Type of the token being read, its start, and the start of its value
+        let mut pending: Option<(TokenType, usize, usize)> = None;
+        let mut state = State::Start;
+        let mut offset = 0;
+        while let Some(ch) = input[offset..].chars().next() {
+            let run = self.run_len(state, &input.as_bytes()[offset..]);
+            if run > 0 {
+                offset += run;
+                continue;
+            }
+            let transition = self
+                .transition(state, ch)
+                .map_err(|error| (error, offset))?;
+            let end = offset + ch.len_utf8();
+            let token = |(token_type, start, value_start), span_end| Emitted::Token {
+                token_type,
+                span: Span::new(start, span_end),
+                value: Span::new(value_start, offset),
+            };
+            let flushed = match transition.flush {
+                Flush::None => None,
+                Flush::Before => pending.take().map(|pending| token(pending, offset)),
+                Flush::Through => pending.take().map(|pending| token(pending, end)),
+                Flush::Empty => Some(Emitted::Empty { offset }),
+            };
+            match transition.collect {
+                Collect::None | Collect::Push => {}
+                Collect::Begin(token_type) => pending = Some((token_type, offset, offset)),
+                Collect::Open(token_type) => pending = Some((token_type, offset, end)),
+            }
+            let punct = match transition.emit {
+                Emit::None => None,
+                Emit::Punct(token_type) => Some(token_type),
+                Emit::TagEnd => Some(TokenType::RightParentheses),
+            };
+            let punct = punct.map(|token_type| Emitted::Punct {
+                token_type,
+                span: Span::new(offset, end),
+            });
+            state = transition.next;
+            offset = end;
+            for emitted in [flushed, punct].into_iter().flatten() {
+                if sink(emitted).is_break() {
+                    return Ok(ControlFlow::Break(offset));
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+static DEFAULT: Table = Table::build(SPEC, false);
+static SELF_CLOSING: Table = Table::build(SPEC, true);
+
+/// A problem found in a transition specification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecIssue {
+    /// Two rules give a transition for the same state and class
+    Ambiguous {
+        state: State,
+        class: CharClass,
+        rules: (usize, usize),
+    },
+    /// No path from `Start` leads to the state
+    Unreachable(State),
+}
+
+/// Check a transition specification for ambiguous rules and unreachable states
+///
+/// Rules marked `self_closing` only take part when `self_closing` is set.
+///
+/// ```rust
+/// use jsx_compilation_rs::dfa::{check, SpecIssue, SPEC};
+/// use jsx_compilation_rs::State;
+///
+/// assert_eq!(check(SPEC, true), vec![]);
+/// assert_eq!(check(SPEC, false), vec![SpecIssue::Unreachable(State::SelfClosingSlash)]);
+/// ```
+pub fn check(spec: &[Rule], self_closing: bool) -> Vec<SpecIssue> {
+    let mut issues = Vec::new();
+    let mut owner: [[Option<usize>; CLASS_COUNT]; STATE_COUNT] = [[None; CLASS_COUNT]; STATE_COUNT];
+    let active = spec
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| self_closing || !rule.self_closing);
+
+    for (index, rule) in active.clone() {
+        for &state in rule.from {
+            for &class in rule.on {
+                match owner[state as usize][class as usize] {
+                    Some(first) => issues.push(SpecIssue::Ambiguous {
+                        state,
+                        class,
+                        rules: (first, index),
+                    }),
+                    None => owner[state as usize][class as usize] = Some(index),
+                }
+            }
+        }
+    }
+
+    let mut reachable = [false; STATE_COUNT];
+    reachable[State::Start as usize] = true;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (_, rule) in active.clone() {
+            let from_reachable = rule.from.iter().any(|&s| reachable[s as usize]);
+            if from_reachable && !rule.on.is_empty() && !reachable[rule.to as usize] {
+                reachable[rule.to as usize] = true;
+                changed = true;
+            }
+        }
+    }
+    issues.extend(
+        State::ALL
+            .iter()
+            .filter(|&&state| !reachable[state as usize])
+            .map(|&state| SpecIssue::Unreachable(state)),
+    );
+    issues
+}
@@ -0,0 +1,304 @@
+use crate::locale::Locale;
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::{tokenize_spanned, State, TokenType, TokenizerError};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on fix passes in [`fix`], guarding against suggestions that never settle
+const MAX_FIX_PASSES: usize = 32;
+
+/// The kind of mistake a [`Suggestion`] repairs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum FixKind {
+    /// `id=title` becomes `id="title"`
+    QuoteAttributeValue,
+    /// `id='title'` becomes `id="title"`
+    UseDoubleQuotes,
+    /// Extra whitespace inside a tag, as in `<div  id="a">` or `<div >`
+    RemoveWhitespace,
+    /// `<img/>` becomes `<img></img>`
+    ExpandSelfClosing { name: String },
+    /// `class=` becomes `className=`
+    RenameAttribute { from: String, to: String },
+    /// `</div>` appended for an element left open at the end of input
+    CloseElement { name: String },
+}
+
+/// A machine-applicable edit: replace `span` in the source with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    #[serde(flatten)]
+    pub kind: FixKind,
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Suggestion {
+    pub fn new(kind: FixKind, span: Span, replacement: impl Into<String>) -> Self {
+        Self {
+            kind,
+            span,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Human-readable description of the fix in the given locale
+    pub fn message(&self, locale: Locale) -> String {
+        locale.format_fix(&self.kind)
+    }
+}
+
+/// A tokenizer error located in the source, with optional fix-it suggestions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub error: TokenizerError,
+    pub span: Span,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(error: TokenizerError, span: Span) -> Self {
+        Self {
+            error,
+            span,
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn with_suggestions(mut self, suggestions: Vec<Suggestion>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// Stable error code of the underlying error
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
+    /// Error message in the given locale
+    pub fn message(&self, locale: Locale) -> String {
+        self.error.message(locale)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.error, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl From<Diagnostic> for TokenizerError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        diagnostic.error
+    }
+}
+
+/// Where and how the state machine failed, used to derive suggestions
+pub(crate) struct FailureContext<'a> {
+    pub input: &'a str,
+    /// State the tokenizer was in when it rejected `ch`
+    pub state: State,
+    /// Byte offset of `ch`
+    pub offset: usize,
+    pub ch: char,
+}
+
+impl FailureContext<'_> {
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let rest = &self.input[self.offset..];
+        match (self.state, self.ch) {
+            // id=title
+            (State::AttributeValue, ch) if ch.is_ascii_alphanumeric() => {
+                let run = take_alphanumeric(rest);
+                let span = Span::new(self.offset, self.offset + run.len());
+                vec![Suggestion::new(
+                    FixKind::QuoteAttributeValue,
+                    span,
+                    format!("\"{}\"", run),
+                )]
+            }
+            // id='title'
+            (State::AttributeValue, '\'') => match rest[1..].find('\'') {
+                Some(close) => {
+                    let inner = &rest[1..close + 1];
+                    let span = Span::new(self.offset, self.offset + close + 2);
+                    vec![Suggestion::new(
+                        FixKind::UseDoubleQuotes,
+                        span,
+                        format!("\"{}\"", inner),
+                    )]
+                }
+                None => Vec::new(),
+            },
+            // <img/>, <img />, <img src="a"/>
+            (State::JSXIdentifier | State::Attribute | State::TryLeaveAttribute, '/')
+                if rest[1..].starts_with('>') =>
+            {
+                let Some(name) = self.open_tag_name() else {
+                    return Vec::new();
+                };
+                let start = if self.state == State::Attribute {
+                    self.offset - 1
+                } else {
+                    self.offset
+                };
+                let span = Span::new(start, self.offset + 2);
+                vec![Suggestion::new(
+                    FixKind::ExpandSelfClosing {
+                        name: name.to_string(),
+                    },
+                    span,
+                    format!("></{}>", name),
+                )]
+            }
+            // <div  id="a">
+            (State::Attribute, ' ') => vec![Suggestion::new(
+                FixKind::RemoveWhitespace,
+                Span::new(self.offset, self.offset + 1),
+                "",
+            )],
+            // <div >
+            (State::Attribute, '>') => vec![Suggestion::new(
+                FixKind::RemoveWhitespace,
+                Span::new(self.offset - 1, self.offset),
+                "",
+            )],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Name of the tag the failing character belongs to
+    fn open_tag_name(&self) -> Option<&str> {
+        let tag_start = self.input[..self.offset].rfind('<')?;
+        let name = take_alphanumeric(&self.input[tag_start + 1..]);
+        (!name.is_empty()).then_some(name)
+    }
+}
+
+fn take_alphanumeric(s: &str) -> &str {
+    let end = s
+        .find(|ch: char| !ch.is_ascii_alphanumeric())
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// HTML attribute names that JSX spells differently
+const RENAMED_ATTRIBUTES: [(&str, &str); 2] = [("class", "className"), ("for", "htmlFor")];
+
+/// Suggestions for input that tokenizes but is probably not what was meant
+fn lint(input: &str, tokens: &[SpannedToken]) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    let mut open_elements: Vec<&str> = Vec::new();
+
+    for (i, spanned) in tokens.iter().enumerate() {
+        let token = &spanned.token;
+        match token.token_type {
+            TokenType::AttributeKey => {
+                if let Some((from, to)) = RENAMED_ATTRIBUTES
+                    .iter()
+                    .find(|(from, _)| *from == token.value)
+                {
+                    suggestions.push(Suggestion::new(
+                        FixKind::RenameAttribute {
+                            from: from.to_string(),
+                            to: to.to_string(),
+                        },
+                        spanned.span,
+                        *to,
+                    ));
+                }
+            }
+            TokenType::JSXIdentifier => {
+                let closing = i > 0 && tokens[i - 1].token.token_type == TokenType::BackSlash;
+                if !closing {
+                    open_elements.push(&token.value);
+                } else if open_elements.last() == Some(&token.value.as_str()) {
+                    open_elements.pop();
+                } else {
+                    // Mismatched nesting cannot be repaired by appending closing tags
+                    open_elements.clear();
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let end = Span::new(input.len(), input.len());
+    for name in open_elements.into_iter().rev() {
+        suggestions.push(Suggestion::new(
+            FixKind::CloseElement {
+                name: name.to_string(),
+            },
+            end,
+            format!("</{}>", name),
+        ));
+    }
+    suggestions
+}
+
+/// Collect fix-it suggestions for the input
+///
+/// If tokenization fails, these are the suggestions attached to the failure;
+/// otherwise they are lints such as `class` → `className` and closing tags for
+/// elements left open.
+pub fn suggest_fixes(input: &str) -> Vec<Suggestion> {
+    match tokenize_spanned(input) {
+        Ok(tokens) => lint(input, &tokens),
+        Err(diagnostic) => diagnostic.suggestions,
+    }
+}
+
+/// Apply suggestions to the source text
+///
+/// Suggestions are applied in source order; one that overlaps an earlier
+/// suggestion is skipped. Insertions at the same offset keep their order.
+pub fn apply_suggestions(source: &str, suggestions: &[Suggestion]) -> String {
+    let mut ordered: Vec<&Suggestion> = suggestions.iter().collect();
+    ordered.sort_by_key(|suggestion| suggestion.span.start);
+
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for suggestion in ordered {
+        if suggestion.span.start < cursor {
+            continue;
+        }
+        output.push_str(&source[cursor..suggestion.span.start]);
+        output.push_str(&suggestion.replacement);
+        cursor = suggestion.span.end;
+    }
+    output.push_str(&source[cursor..]);
+    output
+}
+
+/// Repeatedly apply suggestions until none remain, as a `--fix` mode would
+///
+/// Each tokenizer failure only reveals the first mistake, so fixing runs in
+/// passes until the input is clean or no further suggestion applies.
+///
+/// ```rust
+/// use jsx_compilation_rs::fix;
+///
+/// assert_eq!(fix("<div class=box><img/>"), r#"<div className="box"><img></img></div>"#);
+/// ```
+pub fn fix(input: &str) -> String {
+    let mut source = input.to_string();
+    for _ in 0..MAX_FIX_PASSES {
+        let suggestions = suggest_fixes(&source);
+        if suggestions.is_empty() {
+            break;
+        }
+        let fixed = apply_suggestions(&source, &suggestions);
+        if fixed == source {
+            break;
+        }
+        source = fixed;
+    }
+    source
+}
@@ -0,0 +1,284 @@
+use crate::diagnostic::Diagnostic;
+use crate::locale::Locale;
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::tokenize_spanned;
+use serde::{Deserialize, Serialize};
+
+/// Text encodings accepted by [`decode`] and [`tokenize_bytes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1: every byte is the code point of the same value
+    Latin1,
+}
+
+impl Encoding {
+    /// The encoding's canonical label
+    pub fn label(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Latin1 => "iso-8859-1",
+        }
+    }
+
+    /// The encoding whose byte order mark starts `bytes`, and the mark's length
+    fn sniff_bom(bytes: &[u8]) -> Option<(Encoding, usize)> {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => Some((Encoding::Utf8, 3)),
+            [0xFF, 0xFE, ..] => Some((Encoding::Utf16Le, 2)),
+            [0xFE, 0xFF, ..] => Some((Encoding::Utf16Be, 2)),
+            _ => None,
+        }
+    }
+
+    /// Guess the encoding of input without a byte order mark
+    ///
+    /// JSX starts with `<`, so UTF-16 shows up as a zero byte next to it.
+    fn detect(bytes: &[u8]) -> Encoding {
+        match bytes {
+            [b'<', 0x00, ..] => Encoding::Utf16Le,
+            [0x00, b'<', ..] => Encoding::Utf16Be,
+            _ => Encoding::Utf8,
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Error returned when parsing an unsupported encoding label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEncoding(pub String);
+
+impl std::fmt::Display for UnknownEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unsupported encoding: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownEncoding {}
+
+impl std::str::FromStr for Encoding {
+    type Err = UnknownEncoding;
+
+    /// Parse a label such as `utf-8`, `UTF-16LE` or `latin1` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim().replace('_', "-").to_ascii_lowercase();
+        match label.as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "utf-16le" | "utf-16" => Ok(Encoding::Utf16Le),
+            "utf-16be" => Ok(Encoding::Utf16Be),
+            "iso-8859-1" | "latin1" | "latin-1" | "l1" => Ok(Encoding::Latin1),
+            _ => Err(UnknownEncoding(s.to_string())),
+        }
+    }
+}
+
+/// A byte sequence that is not valid in the input's encoding
+///
+/// It is decoded as U+FFFD, so lexing carries on past it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidBytes {
+    /// The offending bytes, as offsets into the raw input
+    pub span: Span,
+    /// Offset of the replacement character in the decoded text
+    pub offset: usize,
+    pub encoding: Encoding,
+}
+
+impl InvalidBytes {
+    /// Stable code, alongside the [`TokenizerError`](crate::TokenizerError) codes
+    pub fn code(&self) -> &'static str {
+        "JSX0004"
+    }
+
+    /// Message in the given locale
+    pub fn message(&self, locale: Locale) -> String {
+        locale.format_invalid_bytes(self)
+    }
+}
+
+impl std::fmt::Display for InvalidBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for InvalidBytes {}
+
+/// Raw input decoded to text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub text: String,
+    pub encoding: Encoding,
+    /// Length of the byte order mark that was stripped, 0 if there was none
+    pub bom: usize,
+    /// Invalid sequences, in input order
+    pub invalid: Vec<InvalidBytes>,
+    /// Length of the raw input
+    input_len: usize,
+}
+
+impl Decoded {
+    /// Byte offset in the raw input of byte `offset` of [`text`](Self::text)
+    pub fn input_offset(&self, offset: usize) -> usize {
+        let before = &self.text[..offset];
+        let input = match self.encoding {
+            Encoding::Utf8 => {
+                // Each replacement character stands for the invalid bytes, not its own three
+                self.invalid
+                    .iter()
+                    .take_while(|invalid| invalid.offset < offset)
+                    .fold(offset, |input, invalid| input + invalid.span.len() - 3)
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                2 * before.chars().map(char::len_utf16).sum::<usize>()
+            }
+            Encoding::Latin1 => before.chars().count(),
+        };
+        (self.bom + input).min(self.input_len)
+    }
+
+    /// [`input_offset`](Self::input_offset) for both ends of a span
+    pub fn input_span(&self, span: Span) -> Span {
+        Span::new(self.input_offset(span.start), self.input_offset(span.end))
+    }
+
+    /// Record `len` invalid bytes at input offset `start` and replace them
+    fn replace(&mut self, start: usize, len: usize) {
+        self.invalid.push(InvalidBytes {
+            span: Span::new(start, start + len),
+            offset: self.text.len(),
+            encoding: self.encoding,
+        });
+        self.text.push(char::REPLACEMENT_CHARACTER);
+    }
+}
+
+/// Decode raw input, stripping any byte order mark
+///
+/// A byte order mark decides the encoding, even over `encoding`. Without
+/// one, `encoding` is used if given; otherwise UTF-16 is recognized by the
+/// zero byte next to the leading `<`, and anything else is read as UTF-8.
+/// Invalid sequences never fail the decode: each is replaced by U+FFFD and
+/// recorded in [`Decoded::invalid`].
+///
+/// ```rust
+/// use jsx_compilation_rs::{decode, Encoding};
+///
+/// let decoded = decode(b"\xEF\xBB\xBF<p>caf\xE9</p>", None);
+/// assert_eq!(decoded.text, "<p>caf\u{FFFD}</p>");
+/// assert_eq!(decoded.invalid[0].span.start, 9);
+///
+/// let decoded = decode(b"<p>caf\xE9</p>", Some(Encoding::Latin1));
+/// assert_eq!(decoded.text, "<p>café</p>");
+/// assert!(decoded.invalid.is_empty());
+/// ```
+pub fn decode(bytes: &[u8], encoding: Option<Encoding>) -> Decoded {
+    let (encoding, bom) = match Encoding::sniff_bom(bytes) {
+        Some((encoding, bom)) => (encoding, bom),
+        None => (encoding.unwrap_or_else(|| Encoding::detect(bytes)), 0),
+    };
+    let mut decoded = Decoded {
+        text: String::with_capacity(bytes.len()),
+        encoding,
+        bom,
+        invalid: Vec::new(),
+        input_len: bytes.len(),
+    };
+    match encoding {
+        Encoding::Utf8 => decode_utf8(bytes, bom, &mut decoded),
+        Encoding::Utf16Le => decode_utf16(bytes, bom, u16::from_le_bytes, &mut decoded),
+        Encoding::Utf16Be => decode_utf16(bytes, bom, u16::from_be_bytes, &mut decoded),
+        Encoding::Latin1 => decoded.text.extend(bytes.iter().map(|&b| char::from(b))),
+    }
+    decoded
+}
+
+fn decode_utf8(bytes: &[u8], mut offset: usize, decoded: &mut Decoded) {
+    while offset < bytes.len() {
+        match std::str::from_utf8(&bytes[offset..]) {
+            Ok(text) => {
+                decoded.text.push_str(text);
+                return;
+            }
+            Err(e) => {
+                let valid = offset + e.valid_up_to();
+                // `valid_up_to` guarantees these bytes are UTF-8
+                decoded
+                    .text
+                    .push_str(std::str::from_utf8(&bytes[offset..valid]).unwrap_or_default());
+                // A sequence cut off by the end of input has no `error_len`
+                let len = e.error_len().unwrap_or(bytes.len() - valid);
+                decoded.replace(valid, len);
+                offset = valid + len;
+            }
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], start: usize, unit: fn([u8; 2]) -> u16, decoded: &mut Decoded) {
+    let pairs = bytes[start..].chunks_exact(2);
+    let odd = pairs.remainder().len();
+    let units = pairs.map(|pair| unit([pair[0], pair[1]]));
+    let mut offset = start;
+    for result in char::decode_utf16(units) {
+        match result {
+            Ok(ch) => {
+                decoded.text.push(ch);
+                offset += 2 * ch.len_utf16();
+            }
+            Err(_) => {
+                decoded.replace(offset, 2);
+                offset += 2;
+            }
+        }
+    }
+    if odd > 0 {
+        decoded.replace(offset, odd);
+    }
+}
+
+/// The outcome of [`tokenize_bytes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedTokens {
+    /// The decoded text, with any invalid byte sequences
+    pub source: Decoded,
+    /// Token spans are offsets into `source.text`; map them back to the raw
+    /// input with [`Decoded::input_span`]
+    pub tokens: Result<Vec<SpannedToken>, Diagnostic>,
+}
+
+/// Tokenize raw bytes, decoding them first as described at [`decode`]
+///
+/// Invalid byte sequences are reported in `source.invalid` with their byte
+/// offsets, and do not stop lexing: the replacement character is ordinary
+/// text, so a bad byte between tags still yields tokens.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_bytes, Encoding};
+///
+/// // UTF-16LE with a byte order mark
+/// let bytes: Vec<u8> = [0xFF, 0xFE]
+///     .into_iter()
+///     .chain("<p>hi</p>".encode_utf16().flat_map(u16::to_le_bytes))
+///     .collect();
+/// let result = tokenize_bytes(&bytes, None);
+/// assert_eq!(result.source.encoding, Encoding::Utf16Le);
+///
+/// let tokens = result.tokens.unwrap();
+/// assert_eq!(tokens[3].token.value, "hi");
+/// assert_eq!(result.source.input_span(tokens[3].span).start, 8);
+/// ```
+pub fn tokenize_bytes(bytes: &[u8], encoding: Option<Encoding>) -> DecodedTokens {
+    let source = decode(bytes, encoding);
+    let tokens = tokenize_spanned(&source.text);
+    DecodedTokens { source, tokens }
+}
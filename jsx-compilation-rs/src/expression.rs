@@ -0,0 +1,672 @@
+mod lexer;
+
+use crate::ast::{
+    ArrayExpression, ArrowFunctionExpression, BinaryExpression, CallExpression,
+    ConditionalExpression, Expression, Identifier, JSXEmptyExpression, Literal, LiteralValue,
+    LogicalExpression, MemberExpression, ObjectExpression, ObjectMember, Property, SpreadElement,
+    TemplateElement, TemplateLiteral, ThisExpression, UnaryExpression,
+};
+use crate::parser::{parse_element_at, ParseError};
+use crate::span::Span;
+use lexer::{Lexer, TemplateEnd, Token, TokenKind};
+use serde::{Deserialize, Serialize};
+
+/// Why [`parse_expression`] failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ExpressionError {
+    /// A character that starts no token
+    UnexpectedCharacter { character: char, offset: usize },
+    /// A token that cannot appear at this point; `token` is as written
+    UnexpectedToken { token: String, span: Span },
+    /// The expression stopped where more was needed
+    UnexpectedEndOfInput { offset: usize },
+    /// A string, template literal or comment with no end
+    Unterminated { span: Span },
+    /// JavaScript outside the supported subset, such as `new` or assignment
+    Unsupported { construct: String, span: Span },
+    /// JSX inside the expression failed to parse
+    Jsx { error: Box<ParseError> },
+}
+
+impl ExpressionError {
+    /// Where the error is in the source
+    pub fn span(&self) -> Span {
+        match self {
+            ExpressionError::UnexpectedCharacter { character, offset } => {
+                Span::new(*offset, offset + character.len_utf8())
+            }
+            ExpressionError::UnexpectedEndOfInput { offset } => Span::new(*offset, *offset),
+            ExpressionError::UnexpectedToken { span, .. }
+            | ExpressionError::Unterminated { span }
+            | ExpressionError::Unsupported { span, .. } => *span,
+            ExpressionError::Jsx { error } => error.span(),
+        }
+    }
+}
+
+impl std::fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionError::UnexpectedCharacter { character, .. } => {
+                write!(f, "Unexpected character: {}", character)
+            }
+            ExpressionError::UnexpectedToken { token, .. } => {
+                write!(f, "Unexpected token: {}", token)
+            }
+            ExpressionError::UnexpectedEndOfInput { .. } => {
+                write!(f, "Unexpected end of expression")
+            }
+            ExpressionError::Unterminated { .. } => write!(f, "Unterminated literal or comment"),
+            ExpressionError::Unsupported { construct, .. } => {
+                write!(f, "{} is not supported in expressions", construct)
+            }
+            ExpressionError::Jsx { error } => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+/// Binding power of a binary or logical operator; higher binds tighter
+fn precedence(operator: &str) -> Option<u8> {
+    let precedence = match operator {
+        "??" => 1,
+        "||" => 2,
+        "&&" => 3,
+        "|" => 4,
+        "^" => 5,
+        "&" => 6,
+        "==" | "!=" | "===" | "!==" => 7,
+        "<" | ">" | "<=" | ">=" | "in" | "instanceof" => 8,
+        "<<" | ">>" | ">>>" => 9,
+        "+" | "-" => 10,
+        "*" | "/" | "%" => 11,
+        "**" => 12,
+        _ => return None,
+    };
+    Some(precedence)
+}
+
+/// Keywords that start JavaScript the parser does not handle
+const UNSUPPORTED_KEYWORDS: [&str; 8] = [
+    "new", "function", "class", "async", "await", "yield", "super", "import",
+];
+
+/// Recursive descent with precedence climbing for binary operators
+struct Parser<'a> {
+    lexer: Lexer<'a>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Result<Token, ExpressionError> {
+        self.lexer.peek()
+    }
+
+    fn next(&mut self) -> Result<Token, ExpressionError> {
+        self.lexer.next_token()
+    }
+
+    /// Consume the next token if it is `punct`
+    fn eat(&mut self, punct: &str) -> Result<bool, ExpressionError> {
+        if self.peek()?.is_punct(punct) {
+            self.next()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Consume the next token, which must be `punct`; returns its end
+    fn expect(&mut self, punct: &str) -> Result<usize, ExpressionError> {
+        let token = self.next()?;
+        if !token.is_punct(punct) {
+            return Err(self.unexpected(&token));
+        }
+        Ok(token.span.end)
+    }
+
+    fn unexpected(&self, token: &Token) -> ExpressionError {
+        match token.kind {
+            TokenKind::Eof => ExpressionError::UnexpectedEndOfInput {
+                offset: token.span.start,
+            },
+            _ => ExpressionError::UnexpectedToken {
+                token: self.lexer.source()[token.span.start..token.span.end].to_string(),
+                span: token.span,
+            },
+        }
+    }
+
+    /// `assignment -> arrow | conditional`; assignment itself is rejected
+    fn assignment(&mut self) -> Result<Expression, ExpressionError> {
+        if let Some(arrow) = self.arrow()? {
+            return Ok(Expression::ArrowFunctionExpression(arrow));
+        }
+        let expression = self.conditional()?;
+        let next = self.peek()?;
+        if let TokenKind::Punct(punct) = next.kind {
+            if punct.ends_with('=') && precedence(punct).is_none() {
+                return Err(ExpressionError::Unsupported {
+                    construct: "Assignment".to_string(),
+                    span: next.span,
+                });
+            }
+        }
+        Ok(expression)
+    }
+
+    /// `arrow -> (IDENTIFIER | "(" IDENTIFIER,* ")") "=>" assignment`
+    ///
+    /// Returns `None`, having consumed nothing, if no arrow function starts here.
+    fn arrow(&mut self) -> Result<Option<ArrowFunctionExpression>, ExpressionError> {
+        let start = self.lexer.position;
+        let first = self.peek()?;
+        let params = match &first.kind {
+            TokenKind::Identifier(name) if self.lexer.peek_second()?.is_punct("=>") => {
+                self.next()?;
+                vec![Identifier {
+                    name: name.clone(),
+                    span: first.span,
+                }]
+            }
+            TokenKind::Punct("(") => match self.arrow_params()? {
+                Some(params) => params,
+                None => {
+                    self.lexer.position = start;
+                    return Ok(None);
+                }
+            },
+            _ => return Ok(None),
+        };
+        self.expect("=>")?;
+        let next = self.peek()?;
+        if next.is_punct("{") {
+            return Err(ExpressionError::Unsupported {
+                construct: "A block body".to_string(),
+                span: next.span,
+            });
+        }
+        let body = self.assignment()?;
+        Ok(Some(ArrowFunctionExpression {
+            params,
+            span: Span::new(first.span.start, body.span().end),
+            body: Box::new(body),
+            expression: true,
+        }))
+    }
+
+    /// `(a, b)` followed by `=>`, or `None` if the parenthesis is not a parameter list
+    fn arrow_params(&mut self) -> Result<Option<Vec<Identifier>>, ExpressionError> {
+        self.expect("(")?;
+        let mut params = Vec::new();
+        loop {
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Punct(")") => break,
+                TokenKind::Identifier(name) => params.push(Identifier {
+                    name,
+                    span: token.span,
+                }),
+                _ => return Ok(None),
+            }
+            let token = self.next()?;
+            if token.is_punct(")") {
+                break;
+            }
+            if !token.is_punct(",") {
+                return Ok(None);
+            }
+        }
+        Ok(self.peek()?.is_punct("=>").then_some(params))
+    }
+
+    /// `conditional -> binary ("?" assignment ":" assignment)?`
+    fn conditional(&mut self) -> Result<Expression, ExpressionError> {
+        let test = self.binary(0)?;
+        if !self.eat("?")? {
+            return Ok(test);
+        }
+        let consequent = self.assignment()?;
+        self.expect(":")?;
+        let alternate = self.assignment()?;
+        Ok(Expression::ConditionalExpression(ConditionalExpression {
+            span: Span::new(test.span().start, alternate.span().end),
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+        }))
+    }
+
+    /// Binary and logical operators binding at least as tightly as `min`
+    fn binary(&mut self, min: u8) -> Result<Expression, ExpressionError> {
+        let mut left = self.unary()?;
+        loop {
+            let token = self.peek()?;
+            let operator = match &token.kind {
+                TokenKind::Punct(punct) => *punct,
+                TokenKind::Identifier(name) if name == "in" || name == "instanceof" => {
+                    name.as_str()
+                }
+                _ => return Ok(left),
+            };
+            let Some(precedence) = precedence(operator).filter(|precedence| *precedence >= min)
+            else {
+                return Ok(left);
+            };
+            let operator = operator.to_string();
+            self.next()?;
+            // `**` is right-associative; everything else associates to the left
+            let next_min = if operator == "**" {
+                precedence
+            } else {
+                precedence + 1
+            };
+            let right = self.binary(next_min)?;
+            let span = Span::new(left.span().start, right.span().end);
+            let (left_box, right_box) = (Box::new(left), Box::new(right));
+            left = if matches!(operator.as_str(), "&&" | "||" | "??") {
+                Expression::LogicalExpression(LogicalExpression {
+                    operator,
+                    left: left_box,
+                    right: right_box,
+                    span,
+                })
+            } else {
+                Expression::BinaryExpression(BinaryExpression {
+                    operator,
+                    left: left_box,
+                    right: right_box,
+                    span,
+                })
+            };
+        }
+    }
+
+    /// `unary -> ("!" | "-" | "+" | "~" | "typeof" | "void" | "delete") unary | call`
+    fn unary(&mut self) -> Result<Expression, ExpressionError> {
+        let token = self.peek()?;
+        let operator = match &token.kind {
+            TokenKind::Punct(punct @ ("!" | "-" | "+" | "~")) => punct.to_string(),
+            TokenKind::Identifier(name)
+                if matches!(name.as_str(), "typeof" | "void" | "delete") =>
+            {
+                name.clone()
+            }
+            TokenKind::Punct(punct @ ("++" | "--")) => {
+                return Err(ExpressionError::Unsupported {
+                    construct: punct.to_string(),
+                    span: token.span,
+                })
+            }
+            _ => return self.call(),
+        };
+        self.next()?;
+        let argument = self.unary()?;
+        Ok(Expression::UnaryExpression(UnaryExpression {
+            operator,
+            prefix: true,
+            span: Span::new(token.span.start, argument.span().end),
+            argument: Box::new(argument),
+        }))
+    }
+
+    /// A primary expression followed by any number of `.name`, `[index]`,
+    /// `(arguments)` and their optional `?.` forms
+    fn call(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.primary()?;
+        loop {
+            let token = self.peek()?;
+            let optional = token.is_punct("?.");
+            if optional {
+                self.next()?;
+            }
+            let next = if optional { self.peek()? } else { token };
+            let start = expression.span().start;
+            expression = if next.is_punct("(") {
+                self.next()?;
+                let arguments = self.list(")", Self::argument)?;
+                Expression::CallExpression(CallExpression {
+                    callee: Box::new(expression),
+                    arguments,
+                    optional,
+                    span: Span::new(start, self.lexer.position),
+                })
+            } else if next.is_punct("[") {
+                self.next()?;
+                let property = self.assignment()?;
+                let end = self.expect("]")?;
+                Expression::MemberExpression(MemberExpression {
+                    object: Box::new(expression),
+                    property: Box::new(property),
+                    computed: true,
+                    optional,
+                    span: Span::new(start, end),
+                })
+            } else if optional || next.is_punct(".") {
+                if !optional {
+                    self.next()?;
+                }
+                let property = self.property_name()?;
+                Expression::MemberExpression(MemberExpression {
+                    object: Box::new(expression),
+                    span: Span::new(start, property.span.end),
+                    property: Box::new(Expression::Identifier(property)),
+                    computed: false,
+                    optional,
+                })
+            } else if next.kind == TokenKind::Backtick {
+                return Err(ExpressionError::Unsupported {
+                    construct: "A tagged template".to_string(),
+                    span: next.span,
+                });
+            } else {
+                return Ok(expression);
+            };
+        }
+    }
+
+    /// An identifier after `.`, where keywords are allowed too
+    fn property_name(&mut self) -> Result<Identifier, ExpressionError> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Identifier(name) => Ok(Identifier {
+                name,
+                span: token.span,
+            }),
+            _ => Err(self.unexpected(&token)),
+        }
+    }
+
+    /// A call argument or array element: an expression, or `...expression`
+    fn argument(&mut self) -> Result<Expression, ExpressionError> {
+        let token = self.peek()?;
+        if !token.is_punct("...") {
+            return self.assignment();
+        }
+        self.next()?;
+        let argument = self.assignment()?;
+        Ok(Expression::SpreadElement(SpreadElement {
+            span: Span::new(token.span.start, argument.span().end),
+            argument: Box::new(argument),
+        }))
+    }
+
+    /// Comma-separated items up to `close`, allowing a trailing comma; the
+    /// opening bracket has been consumed
+    fn list<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ExpressionError>,
+    ) -> Result<Vec<T>, ExpressionError> {
+        let mut items = Vec::new();
+        while !self.eat(close)? {
+            items.push(item(self)?);
+            if !self.eat(",")? {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn primary(&mut self) -> Result<Expression, ExpressionError> {
+        let token = self.next()?;
+        let span = token.span;
+        match token.kind {
+            TokenKind::Number(value) => Ok(self.literal(LiteralValue::Number(value), span)),
+            TokenKind::String(ref value) => {
+                Ok(self.literal(LiteralValue::String(value.clone()), span))
+            }
+            TokenKind::Identifier(ref name) => match name.as_str() {
+                "null" => Ok(self.literal(LiteralValue::Null, span)),
+                "true" => Ok(self.literal(LiteralValue::Boolean(true), span)),
+                "false" => Ok(self.literal(LiteralValue::Boolean(false), span)),
+                "this" => Ok(Expression::ThisExpression(ThisExpression { span })),
+                name if UNSUPPORTED_KEYWORDS.contains(&name) => Err(ExpressionError::Unsupported {
+                    construct: format!("`{}`", name),
+                    span,
+                }),
+                name => Ok(Expression::Identifier(Identifier {
+                    name: name.to_string(),
+                    span,
+                })),
+            },
+            TokenKind::Punct("(") => {
+                let expression = self.assignment()?;
+                if self.peek()?.is_punct(",") {
+                    return Err(ExpressionError::Unsupported {
+                        construct: "A sequence expression".to_string(),
+                        span: self.peek()?.span,
+                    });
+                }
+                self.expect(")")?;
+                Ok(expression)
+            }
+            TokenKind::Punct("[") => self.array(span.start),
+            TokenKind::Punct("{") => self.object(span.start),
+            TokenKind::Punct("<") => {
+                let element =
+                    parse_element_at(self.lexer.source(), span.start).map_err(|error| {
+                        ExpressionError::Jsx {
+                            error: Box::new(error),
+                        }
+                    })?;
+                self.lexer.position = element.span.end;
+                Ok(Expression::JSXElement(Box::new(element)))
+            }
+            TokenKind::Backtick => self.template(span.start),
+            // Where an operand is expected, `/` can only start a regular expression
+            TokenKind::Punct("/") | TokenKind::Punct("/=") => Err(ExpressionError::Unsupported {
+                construct: "A regular expression".to_string(),
+                span,
+            }),
+            _ => Err(self.unexpected(&token)),
+        }
+    }
+
+    fn literal(&self, value: LiteralValue, span: Span) -> Expression {
+        Expression::Literal(Literal {
+            value,
+            raw: self.lexer.source()[span.start..span.end].to_string(),
+            span,
+        })
+    }
+
+    /// `[a, , ...b]`, after the `[`
+    fn array(&mut self, start: usize) -> Result<Expression, ExpressionError> {
+        let mut elements = Vec::new();
+        loop {
+            if self.eat("]")? {
+                break;
+            }
+            if self.eat(",")? {
+                elements.push(None);
+                continue;
+            }
+            elements.push(Some(self.argument()?));
+            if !self.eat(",")? {
+                self.expect("]")?;
+                break;
+            }
+        }
+        Ok(Expression::ArrayExpression(ArrayExpression {
+            elements,
+            span: Span::new(start, self.lexer.position),
+        }))
+    }
+
+    /// `{ key: value, shorthand, [computed]: value, ...spread }`, after the `{`
+    fn object(&mut self, start: usize) -> Result<Expression, ExpressionError> {
+        let properties = self.list("}", Self::object_member)?;
+        Ok(Expression::ObjectExpression(ObjectExpression {
+            properties,
+            span: Span::new(start, self.lexer.position),
+        }))
+    }
+
+    fn object_member(&mut self) -> Result<ObjectMember, ExpressionError> {
+        let token = self.next()?;
+        let start = token.span.start;
+        let (key, computed) = match token.kind {
+            TokenKind::Punct("...") => {
+                let argument = self.assignment()?;
+                return Ok(ObjectMember::SpreadElement(SpreadElement {
+                    span: Span::new(start, argument.span().end),
+                    argument: Box::new(argument),
+                }));
+            }
+            TokenKind::Punct("[") => {
+                let key = self.assignment()?;
+                self.expect("]")?;
+                (key, true)
+            }
+            TokenKind::Identifier(ref name) => {
+                let key = Identifier {
+                    name: name.clone(),
+                    span: token.span,
+                };
+                let next = self.peek()?;
+                if next.is_punct(",") || next.is_punct("}") {
+                    return Ok(ObjectMember::Property(Property {
+                        key: Expression::Identifier(key.clone()),
+                        value: Expression::Identifier(key),
+                        computed: false,
+                        shorthand: true,
+                        span: token.span,
+                    }));
+                }
+                (Expression::Identifier(key), false)
+            }
+            TokenKind::String(ref value) => (
+                self.literal(LiteralValue::String(value.clone()), token.span),
+                false,
+            ),
+            TokenKind::Number(value) => {
+                (self.literal(LiteralValue::Number(value), token.span), false)
+            }
+            _ => return Err(self.unexpected(&token)),
+        };
+        let next = self.peek()?;
+        if next.is_punct("(") {
+            return Err(ExpressionError::Unsupported {
+                construct: "A method".to_string(),
+                span: next.span,
+            });
+        }
+        self.expect(":")?;
+        let value = self.assignment()?;
+        Ok(ObjectMember::Property(Property {
+            key,
+            span: Span::new(start, value.span().end),
+            value,
+            computed,
+            shorthand: false,
+        }))
+    }
+
+    /// `` `text ${expression} text` ``, after the opening `` ` ``
+    fn template(&mut self, start: usize) -> Result<Expression, ExpressionError> {
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+        loop {
+            let (value, span, end) = self.lexer.template_chunk()?;
+            let tail = end == TemplateEnd::Tail;
+            quasis.push(TemplateElement { value, tail, span });
+            if tail {
+                break;
+            }
+            expressions.push(self.assignment()?);
+            // The lexer picks the template text up again right after the `}`
+            self.expect("}")?;
+        }
+        Ok(Expression::TemplateLiteral(TemplateLiteral {
+            quasis,
+            expressions,
+            span: Span::new(start, self.lexer.position),
+        }))
+    }
+}
+
+/// Find the `}` closing the expression container whose code starts at `start`
+///
+/// The code is parsed to find it, so braces inside strings, template literals,
+/// objects and JSX are passed over. Returns the offset of the `}`.
+pub(crate) fn container_end(source: &str, start: usize) -> Result<usize, ExpressionError> {
+    let mut parser = Parser {
+        lexer: Lexer::new(source, start),
+    };
+    if !parser.peek()?.is_punct("}") {
+        parser.assignment()?;
+    }
+    let token = parser.next()?;
+    if !token.is_punct("}") {
+        return Err(parser.unexpected(&token));
+    }
+    Ok(token.span.start)
+}
+
+/// The value of `raw` if it is a single number literal
+pub(crate) fn number_value(raw: &str) -> Option<f64> {
+    let mut lexer = Lexer::new(raw, 0);
+    match lexer.next_token().ok()?.kind {
+        TokenKind::Number(value) if lexer.position == raw.len() => Some(value),
+        _ => None,
+    }
+}
+
+/// Parse the expression in `source[span]`, with spans into all of `source`
+///
+/// Nothing but whitespace and comments gives a [`JSXEmptyExpression`].
+pub(crate) fn parse_expression_in(source: &str, span: Span) -> Result<Expression, ExpressionError> {
+    let mut parser = Parser {
+        lexer: Lexer::new(&source[..span.end], span.start),
+    };
+    if parser.peek()?.kind == TokenKind::Eof {
+        return Ok(Expression::JSXEmptyExpression(JSXEmptyExpression { span }));
+    }
+    let expression = parser.assignment()?;
+    let token = parser.next()?;
+    if token.kind != TokenKind::Eof {
+        return Err(parser.unexpected(&token));
+    }
+    Ok(expression)
+}
+
+/// Parse a JavaScript expression into ESTree nodes
+///
+/// Handles a practical subset of JavaScript: literals (including template
+/// literals), identifiers and `this`, member and call expressions with
+/// optional chaining, arrow functions with identifier parameters and an
+/// expression body, conditional, logical, binary and unary operators,
+/// array and object literals, spread, and JSX elements. Operators bind as in
+/// JavaScript. Statements, assignment, `new`, `function`, classes and regular
+/// expression literals are not supported. A `/` where an operand is expected
+/// fails with [`ExpressionError::Unsupported`] rather than being read as
+/// division, as in `s.replace(/x/g, "")`.
+///
+/// JSX inside the expression is parsed as by [`parse`](crate::parse), except
+/// that self-closing tags are allowed.
+///
+/// ```rust
+/// use jsx_compilation_rs::ast::Expression;
+/// use jsx_compilation_rs::parse_expression;
+///
+/// let Expression::CallExpression(call) = parse_expression("items.map(i => <li>item</li>)").unwrap() else {
+///     panic!("expected a call");
+/// };
+/// assert!(matches!(*call.callee, Expression::MemberExpression(_)));
+/// assert!(matches!(&call.arguments[0], Expression::ArrowFunctionExpression(arrow)
+///     if matches!(*arrow.body, Expression::JSXElement(_))));
+///
+/// let json = serde_json::to_value(parse_expression("a ? b : c").unwrap()).unwrap();
+/// assert_eq!(json["type"], "ConditionalExpression");
+/// assert_eq!(json["test"]["name"], "a");
+/// ```
+pub fn parse_expression(input: &str) -> Result<Expression, ExpressionError> {
+    match parse_expression_in(input, Span::new(0, input.len()))? {
+        Expression::JSXEmptyExpression(_) => Err(ExpressionError::UnexpectedEndOfInput {
+            offset: input.len(),
+        }),
+        expression => Ok(expression),
+    }
+}
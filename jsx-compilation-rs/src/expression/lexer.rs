@@ -0,0 +1,323 @@
+use super::ExpressionError;
+use crate::ast::TemplateElementValue;
+use crate::span::Span;
+
+/// Punctuators, longer ones first so that the first match is the longest
+const PUNCTUATORS: &[&str] = &[
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "**", "<<", ">>", "++", "--", "+=", "-=", "*=", "/=", "%=",
+    "&=", "|=", "^=", "(", ")", "[", "]", "{", "}", ",", ":", "?", ".", "+", "-", "*", "/", "%",
+    "<", ">", "!", "~", "&", "|", "^", "=",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TokenKind {
+    /// An identifier or keyword
+    Identifier(String),
+    Number(f64),
+    /// A string literal, with escapes decoded
+    String(String),
+    Punct(&'static str),
+    /// The `` ` `` opening a template literal
+    Backtick,
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Token {
+    pub(super) kind: TokenKind,
+    pub(super) span: Span,
+}
+
+impl Token {
+    pub(super) fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.kind, TokenKind::Punct(found) if found == punct)
+    }
+}
+
+/// How a chunk of template text ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TemplateEnd {
+    /// At `${`, with an expression to follow
+    Interpolation,
+    /// At the closing `` ` ``
+    Tail,
+}
+
+/// Lexes JavaScript on demand, so the parser can switch to template text or
+/// JSX where the grammar calls for it
+#[derive(Debug, Clone)]
+pub(super) struct Lexer<'a> {
+    /// The input up to where the expression ends; offsets are into all of it
+    source: &'a str,
+    pub(super) position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(super) fn new(source: &'a str, position: usize) -> Self {
+        Self { source, position }
+    }
+
+    pub(super) fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The next token, without consuming it
+    pub(super) fn peek(&self) -> Result<Token, ExpressionError> {
+        self.clone().next_token()
+    }
+
+    /// The token after the next one, without consuming either
+    pub(super) fn peek_second(&self) -> Result<Token, ExpressionError> {
+        let mut lexer = self.clone();
+        lexer.next_token()?;
+        lexer.next_token()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), ExpressionError> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                let close = comment.find("*/").ok_or(ExpressionError::Unterminated {
+                    span: Span::new(self.position, self.source.len()),
+                })?;
+                self.position += close + 4;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    pub(super) fn next_token(&mut self) -> Result<Token, ExpressionError> {
+        self.skip_trivia()?;
+        let start = self.position;
+        let rest = self.rest();
+        let Some(ch) = rest.chars().next() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                span: Span::new(start, start),
+            });
+        };
+        let next = rest[ch.len_utf8()..].chars().next();
+        let kind = match ch {
+            ch if is_identifier_start(ch) => {
+                let len = rest
+                    .find(|ch: char| !is_identifier_part(ch))
+                    .unwrap_or(rest.len());
+                self.position += len;
+                TokenKind::Identifier(rest[..len].to_string())
+            }
+            '0'..='9' => self.number()?,
+            '.' if next.is_some_and(|next| next.is_ascii_digit()) => self.number()?,
+            '"' | '\'' => self.string(ch)?,
+            '`' => {
+                self.position += 1;
+                TokenKind::Backtick
+            }
+            _ => {
+                // `a?.5:b` is a conditional, not an optional chain
+                let punct = if rest.starts_with("?.")
+                    && rest[2..].starts_with(|ch: char| ch.is_ascii_digit())
+                {
+                    "?"
+                } else {
+                    PUNCTUATORS
+                        .iter()
+                        .find(|punct| rest.starts_with(**punct))
+                        .ok_or(ExpressionError::UnexpectedCharacter {
+                            character: ch,
+                            offset: start,
+                        })?
+                };
+                self.position += punct.len();
+                TokenKind::Punct(punct)
+            }
+        };
+        Ok(Token {
+            kind,
+            span: Span::new(start, self.position),
+        })
+    }
+
+    /// A decimal, hexadecimal, octal or binary number
+    fn number(&mut self) -> Result<TokenKind, ExpressionError> {
+        let start = self.position;
+        let rest = self.rest();
+        let radix = match rest.get(..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+        let value = if radix == 10 {
+            let bytes = rest.as_bytes();
+            let digits = |from: usize| {
+                bytes[from..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit() || **b == b'_')
+                    .count()
+            };
+            let mut len = digits(0);
+            if bytes.get(len) == Some(&b'.') {
+                len += 1 + digits(len + 1);
+            }
+            if matches!(bytes.get(len), Some(b'e' | b'E')) {
+                let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+                let exponent = digits(len + 1 + sign);
+                if exponent > 0 {
+                    len += 1 + sign + exponent;
+                }
+            }
+            self.position += len;
+            rest[..len].replace('_', "").parse().ok()
+        } else {
+            let len = 2 + rest[2..]
+                .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                .unwrap_or(rest.len() - 2);
+            self.position += len;
+            u64::from_str_radix(&rest[2..len].replace('_', ""), radix)
+                .ok()
+                .map(|value| value as f64)
+        };
+        if self.rest().starts_with(is_identifier_start) {
+            let character = self.rest().chars().next().unwrap_or_default();
+            return Err(ExpressionError::UnexpectedCharacter {
+                character,
+                offset: self.position,
+            });
+        }
+        value.map(TokenKind::Number).ok_or_else(|| {
+            let character = self.source[start..].chars().next().unwrap_or_default();
+            ExpressionError::UnexpectedCharacter {
+                character,
+                offset: start,
+            }
+        })
+    }
+
+    /// A string literal quoted with `quote`
+    fn string(&mut self, quote: char) -> Result<TokenKind, ExpressionError> {
+        let start = self.position;
+        let mut value = String::new();
+        let mut chars = self.rest()[1..].char_indices();
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                ch if ch == quote => {
+                    self.position += 1 + index + 1;
+                    return Ok(TokenKind::String(value));
+                }
+                '\\' => {
+                    let Some((_, escaped)) = chars.next() else {
+                        break;
+                    };
+                    unescape(escaped, &mut chars, &mut value);
+                }
+                '\n' => break,
+                ch => value.push(ch),
+            }
+        }
+        Err(ExpressionError::Unterminated {
+            span: Span::new(start, self.source.len()),
+        })
+    }
+
+    /// Template text after `` ` `` or the `}` of an interpolation
+    ///
+    /// Consumes the text and the `${` or `` ` `` that ends it; the span is
+    /// the text alone.
+    pub(super) fn template_chunk(
+        &mut self,
+    ) -> Result<(TemplateElementValue, Span, TemplateEnd), ExpressionError> {
+        let start = self.position;
+        let rest = self.rest();
+        let mut cooked = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((index, ch)) = chars.next() {
+            let end = match ch {
+                '`' => Some((TemplateEnd::Tail, 1)),
+                '$' if rest[index + 1..].starts_with('{') => Some((TemplateEnd::Interpolation, 2)),
+                '\\' => {
+                    let Some((_, escaped)) = chars.next() else {
+                        break;
+                    };
+                    unescape(escaped, &mut chars, &mut cooked);
+                    None
+                }
+                ch => {
+                    cooked.push(ch);
+                    None
+                }
+            };
+            if let Some((end, delimiter)) = end {
+                let value = TemplateElementValue {
+                    raw: rest[..index].to_string(),
+                    cooked,
+                };
+                self.position += index + delimiter;
+                return Ok((value, Span::new(start, start + index), end));
+            }
+        }
+        Err(ExpressionError::Unterminated {
+            span: Span::new(start.saturating_sub(1), self.source.len()),
+        })
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_' || ch == '$'
+}
+
+fn is_identifier_part(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
+
+/// Decode the escape sequence `\escaped`, reading any further characters it needs from `chars`
+fn unescape(escaped: char, chars: &mut std::str::CharIndices, out: &mut String) {
+    fn hex(chars: &mut std::str::CharIndices, digits: usize) -> Option<char> {
+        let code: String = chars.clone().take(digits).map(|(_, ch)| ch).collect();
+        match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+            Some(ch) if code.len() == digits => {
+                chars.nth(digits - 1);
+                Some(ch)
+            }
+            _ => None,
+        }
+    }
+    let decoded = match escaped {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        'b' => Some('\u{8}'),
+        'f' => Some('\u{c}'),
+        'v' => Some('\u{b}'),
+        '0' => Some('\0'),
+        'x' => hex(chars, 2),
+        'u' if chars.clone().next().map(|(_, ch)| ch) == Some('{') => {
+            let code: String = chars
+                .clone()
+                .skip(1)
+                .map(|(_, ch)| ch)
+                .take_while(|ch| *ch != '}')
+                .collect();
+            let decoded = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32);
+            if decoded.is_some() {
+                chars.nth(code.len() + 1);
+            }
+            decoded
+        }
+        'u' => hex(chars, 4),
+        // A line continuation adds nothing
+        '\n' => return,
+        escaped => Some(escaped),
+    };
+    out.push(decoded.unwrap_or(escaped));
+}
@@ -0,0 +1,215 @@
+use crate::diagnostic::Diagnostic;
+use crate::locale::Locale;
+use crate::nesting::NestingError;
+use crate::span::{Position, Span};
+
+const RED_BOLD: &str = "\x1b[1;31m";
+const BLUE_BOLD: &str = "\x1b[1;34m";
+const CYAN_BOLD: &str = "\x1b[1;36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// How a code frame is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameOptions {
+    /// Number of source lines shown above and below the highlighted lines
+    pub context_lines: usize,
+    /// Whether to emit ANSI color escapes
+    pub color: bool,
+}
+
+impl Default for FrameOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 2,
+            color: false,
+        }
+    }
+}
+
+impl FrameOptions {
+    /// Default options with ANSI colors enabled
+    pub fn colored() -> Self {
+        Self {
+            color: true,
+            ..Self::default()
+        }
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Render the lines around `span` with line numbers, underlining the span
+///
+/// The `label` is printed after the underline on the last highlighted line.
+///
+/// ```text
+///   |
+/// 1 | <div@>
+///   |     ^ Unexpected character: @
+/// ```
+pub fn render_code_frame(source: &str, span: Span, label: &str, options: FrameOptions) -> String {
+    let lines: Vec<&str> = source.split('\n').collect();
+    let start = Position::of(source, span.start);
+    let end = Position::of(source, span.end);
+
+    let first = start.line.saturating_sub(options.context_lines).max(1);
+    let last = last_frame_line(source, span, options);
+    let gutter_width = last.to_string().len();
+    let blank_gutter = options.paint(BLUE_BOLD, &format!("{} |", " ".repeat(gutter_width)));
+
+    let mut out = String::new();
+    out.push_str(&blank_gutter);
+    out.push('\n');
+    for (index, line) in lines.iter().enumerate().take(last).skip(first - 1) {
+        let number = index + 1;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let gutter = options.paint(
+            BLUE_BOLD,
+            &format!("{:>width$} |", number, width = gutter_width),
+        );
+        if line.is_empty() {
+            out.push_str(&gutter);
+        } else {
+            out.push_str(&format!("{} {}", gutter, line));
+        }
+        out.push('\n');
+
+        if number < start.line || number > end.line {
+            continue;
+        }
+        let line_len = line.chars().count();
+        let from = if number == start.line {
+            start.column - 1
+        } else {
+            0
+        };
+        let to = if number == end.line {
+            end.column - 1
+        } else {
+            line_len
+        };
+        // Keep tabs so the underline lines up with the source in any tab width
+        let padding: String = line
+            .chars()
+            .take(from)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let underline = "^".repeat(to.saturating_sub(from).max(1));
+        out.push_str(&format!(
+            "{} {}{}",
+            blank_gutter,
+            padding,
+            options.paint(RED_BOLD, &underline)
+        ));
+        if number == end.line && !label.is_empty() {
+            out.push(' ');
+            out.push_str(&options.paint(RED_BOLD, label));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Number of the last source line shown in the frame for `span`
+fn last_frame_line(source: &str, span: Span, options: FrameOptions) -> usize {
+    let line_count = source.matches('\n').count() + 1;
+    let end = Position::of(source, span.end);
+    (end.line + options.context_lines).min(line_count)
+}
+
+impl Diagnostic {
+    /// Render the diagnostic as a code frame with a header and fix-it hints
+    ///
+    /// ```text
+    /// error[JSX0002]: Unexpected character: @
+    ///  --> 1:5
+    ///   |
+    /// 1 | <div@>
+    ///   |     ^ Unexpected character: @
+    /// ```
+    pub fn render(&self, source: &str, locale: Locale, options: FrameOptions) -> String {
+        let message = self.message(locale);
+        let position = Position::of(source, self.span.start);
+        let gutter_width = last_frame_line(source, self.span, options)
+            .to_string()
+            .len();
+
+        let mut out = format!(
+            "{}{}\n{}{} {}\n",
+            options.paint(RED_BOLD, &format!("error[{}]", self.code())),
+            options.paint(BOLD, &format!(": {}", message)),
+            " ".repeat(gutter_width),
+            options.paint(BLUE_BOLD, "-->"),
+            position,
+        );
+        out.push_str(&render_code_frame(source, self.span, &message, options));
+        for suggestion in &self.suggestions {
+            out.push_str(&format!(
+                "{} {}: {}\n",
+                options.paint(BLUE_BOLD, &format!("{} =", " ".repeat(gutter_width))),
+                options.paint(CYAN_BOLD, "help"),
+                suggestion.message(locale),
+            ));
+        }
+        out
+    }
+}
+
+impl NestingError {
+    /// Render the error as a code frame with a header
+    ///
+    /// A mismatch gets a second frame pointing at the opening tag:
+    ///
+    /// ```text
+    /// error[JSX0005]: Expected </span> but found </div>
+    ///  --> 1:12
+    ///   |
+    /// 1 | <div><span></div>
+    ///   |            ^^^^^^ Expected </span> but found </div>
+    ///  --> 1:6
+    ///   |
+    /// 1 | <div><span></div>
+    ///   |      ^^^^^^ <span> is opened here
+    /// ```
+    pub fn render(&self, source: &str, locale: Locale, options: FrameOptions) -> String {
+        let message = self.message(locale);
+        let span = self.span();
+        let arrow = |span: Span| {
+            let gutter_width = last_frame_line(source, span, options).to_string().len();
+            format!(
+                "{}{} {}\n",
+                " ".repeat(gutter_width),
+                options.paint(BLUE_BOLD, "-->"),
+                Position::of(source, span.start),
+            )
+        };
+
+        let mut out = format!(
+            "{}{}\n",
+            options.paint(RED_BOLD, &format!("error[{}]", self.code())),
+            options.paint(BOLD, &format!(": {}", message)),
+        );
+        out.push_str(&arrow(span));
+        out.push_str(&render_code_frame(source, span, &message, options));
+        if let NestingError::MismatchedClosingTag {
+            expected, opening, ..
+        } = self
+        {
+            out.push_str(&arrow(*opening));
+            out.push_str(&render_code_frame(
+                source,
+                *opening,
+                &locale.format_opened_here(expected),
+                options,
+            ));
+        }
+        out
+    }
+}
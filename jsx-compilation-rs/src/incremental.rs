@@ -0,0 +1,144 @@
+use crate::diagnostic::Diagnostic;
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::{TokenType, Tokenizer};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A text edit: replace the bytes in `range` of the old source with `text`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub range: Span,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Span, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    /// The source after this edit is applied to `old_source`
+    pub fn apply(&self, old_source: &str) -> String {
+        let mut source = String::with_capacity(old_source.len() + self.text.len());
+        source.push_str(&old_source[..self.range.start]);
+        source.push_str(&self.text);
+        source.push_str(&old_source[self.range.end..]);
+        source
+    }
+
+    /// End of the inserted text in the new source
+    fn new_end(&self) -> usize {
+        self.range.start + self.text.len()
+    }
+
+    /// Map an offset at or after the edit in the old source to the new source
+    fn shift(&self, old_offset: usize) -> usize {
+        old_offset - self.range.end + self.new_end()
+    }
+}
+
+/// Result of re-tokenizing after an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retokenized {
+    /// The complete token list for the new source
+    pub tokens: Vec<SpannedToken>,
+    /// Indices in `tokens` that were produced by re-lexing; the rest were reused
+    pub changed: Range<usize>,
+    /// Indices in the old token list that `changed` replaces
+    pub replaced: Range<usize>,
+}
+
+/// Re-tokenize `new_source` after `edit`, reusing tokens from `old_tokens`
+///
+/// `old_tokens` must be the spanned tokens of the source before the edit.
+/// The state after a `<` or `>` token never depends on what came before it,
+/// so lexing restarts at the last such token before the edit and stops as
+/// soon as it emits one past the edit that matches a token in the old list;
+/// from there on the old tokens are reused with their spans shifted.
+///
+/// ```rust
+/// use jsx_compilation_rs::{retokenize, tokenize_spanned, Span, TextEdit};
+///
+/// let old_source = "<ul><li>one</li><li>two</li></ul>";
+/// let old_tokens = tokenize_spanned(old_source).unwrap();
+///
+/// let edit = TextEdit::new(Span::new(8, 11), "uno");
+/// let new_source = edit.apply(old_source);
+/// let result = retokenize(&new_source, &old_tokens, &edit).unwrap();
+///
+/// assert_eq!(result.tokens, tokenize_spanned(&new_source).unwrap());
+/// // Only the text token was re-lexed
+/// assert_eq!(result.changed, 6..7);
+/// ```
+pub fn retokenize(
+    new_source: &str,
+    old_tokens: &[SpannedToken],
+    edit: &TextEdit,
+) -> Result<Retokenized, Diagnostic> {
+    // Safe restart point: just before the last `<` or just after the last `>`
+    // that lies wholly before the edit
+    let (restart_index, restart_offset) = old_tokens
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(i, t)| match t.token.token_type {
+            _ if t.span.end > edit.range.start => None,
+            TokenType::LeftParentheses => Some((i, t.span.start)),
+            TokenType::RightParentheses => Some((i + 1, t.span.end)),
+            _ => None,
+        })
+        .unwrap_or((0, 0));
+
+    let mut tokenizer = if restart_offset == 0 {
+        Tokenizer::new()
+    } else {
+        Tokenizer::resume_between_tags(restart_offset)
+    };
+    let mut relexed = Vec::new();
+    let mut resume_index = old_tokens.len();
+
+    for ch in new_source[restart_offset..].chars() {
+        tokenizer.process_char_diagnosed(new_source, ch)?;
+        let before = relexed.len();
+        relexed.extend(tokenizer.drain_spanned());
+
+        // Resynchronize on a `<` or `>` in text the edit did not touch
+        if let Some(last) = relexed[before..].last() {
+            let token_type = &last.token.token_type;
+            let is_tag_boundary = matches!(
+                token_type,
+                TokenType::LeftParentheses | TokenType::RightParentheses
+            );
+            if is_tag_boundary && last.span.start >= edit.new_end() {
+                let old_start = last.span.start - edit.new_end() + edit.range.end;
+                let index = old_tokens.partition_point(|t| t.span.start < old_start);
+                if old_tokens
+                    .get(index)
+                    .is_some_and(|t| t.span.start == old_start && &t.token.token_type == token_type)
+                {
+                    relexed.pop();
+                    resume_index = index;
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut tokens =
+        Vec::with_capacity(restart_index + relexed.len() + old_tokens.len() - resume_index);
+    tokens.extend_from_slice(&old_tokens[..restart_index]);
+    let changed = tokens.len()..tokens.len() + relexed.len();
+    tokens.extend(relexed);
+    tokens.extend(old_tokens[resume_index..].iter().map(|t| {
+        let span = Span::new(edit.shift(t.span.start), edit.shift(t.span.end));
+        SpannedToken::new(t.token.clone(), span)
+    }));
+
+    Ok(Retokenized {
+        tokens,
+        changed,
+        replaced: restart_index..resume_index,
+    })
+}
@@ -0,0 +1,201 @@
+use crate::dfa::{Emitted, Table};
+use crate::tokenizer::{Token, TokenType, TokenizerError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+/// An interned string, compared and hashed as an integer
+///
+/// Only meaningful together with the [`Interner`] that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Position of the string in its interner, in interning order
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Table of interned strings
+///
+/// Serializes as the list of strings in interning order, so symbols keep
+/// their meaning after a round trip. Each string is allocated once and shared
+/// between the lookup map and the symbol table.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    strings: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The symbol for `name`, adding it if it is new
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        let name: Arc<str> = Arc::from(name);
+        self.strings.push(Arc::clone(&name));
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// The symbol for `name` if it has been interned
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// The string behind `symbol`, or `None` if it came from another interner
+    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+        self.strings.get(symbol.index()).map(|name| &**name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Serialize for Interner {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.strings.iter().map(|name| &**name))
+    }
+}
+
+impl<'de> Deserialize<'de> for Interner {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let strings = Vec::<String>::deserialize(deserializer)?;
+        let mut interner = Interner::new();
+        for name in &strings {
+            interner.intern(name);
+        }
+        if interner.len() != strings.len() {
+            return Err(serde::de::Error::custom("duplicate string in interner"));
+        }
+        Ok(interner)
+    }
+}
+
+/// Value of an [`InternedToken`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InternedValue {
+    /// Tag names and attribute keys
+    Symbol(Symbol),
+    /// Everything else, kept as written
+    Text(String),
+}
+
+/// A token whose identifier or attribute key is stored as a [`Symbol`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternedToken {
+    #[serde(rename = "type")]
+    pub token_type: TokenType,
+    pub value: InternedValue,
+}
+
+impl InternedToken {
+    /// Intern the value of a `JSXIdentifier` or `AttributeKey` token
+    pub fn new(token: Token, interner: &mut Interner) -> Self {
+        if is_interned(token.token_type) {
+            return Self::from_slice(token.token_type, &token.value, interner);
+        }
+        Self {
+            token_type: token.token_type,
+            value: InternedValue::Text(token.value),
+        }
+    }
+
+    /// A token whose value is a slice of the input; only text is copied
+    fn from_slice(token_type: TokenType, value: &str, interner: &mut Interner) -> Self {
+        let value = if is_interned(token_type) {
+            InternedValue::Symbol(interner.intern(value))
+        } else {
+            InternedValue::Text(value.to_string())
+        };
+        Self { token_type, value }
+    }
+
+    /// The symbol, for tag names and attribute keys
+    pub fn symbol(&self) -> Option<Symbol> {
+        match self.value {
+            InternedValue::Symbol(symbol) => Some(symbol),
+            InternedValue::Text(_) => None,
+        }
+    }
+
+    /// The token's text; `None` if its symbol came from another interner
+    pub fn text<'a>(&'a self, interner: &'a Interner) -> Option<&'a str> {
+        match &self.value {
+            InternedValue::Symbol(symbol) => interner.resolve(*symbol),
+            InternedValue::Text(text) => Some(text),
+        }
+    }
+
+    /// Convert back to a plain [`Token`]
+    pub fn resolve(&self, interner: &Interner) -> Option<Token> {
+        let value = self.text(interner)?.to_string();
+        Some(Token::new(self.token_type, value))
+    }
+}
+
+/// Whether tokens of this type have their value interned
+fn is_interned(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::JSXIdentifier | TokenType::AttributeKey
+    )
+}
+
+/// Tokenize JSX, interning tag names and attribute keys into `interner`
+///
+/// Share one interner across inputs so that every `div` in a codebase is the
+/// same symbol. Produces the same tokens as [`tokenizer`](crate::tokenizer()),
+/// but reads names straight out of `input`: a name already in the interner
+/// costs no allocation.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_interned, Interner};
+///
+/// let mut interner = Interner::new();
+/// let first = tokenize_interned(r#"<div class="a">x</div>"#, &mut interner).unwrap();
+/// let second = tokenize_interned("<div>y</div>", &mut interner).unwrap();
+///
+/// assert_eq!(first[1].symbol(), second[1].symbol());
+/// assert_eq!(first[2].text(&interner), Some("class"));
+/// assert_eq!(interner.len(), 2);
+/// ```
+pub fn tokenize_interned(
+    input: &str,
+    interner: &mut Interner,
+) -> Result<Vec<InternedToken>, TokenizerError> {
+    let mut tokens = Vec::new();
+    Table::get(false)
+        .drive(input, |emitted| {
+            let token = match emitted {
+                Emitted::Token {
+                    token_type, value, ..
+                } => InternedToken::from_slice(token_type, value.slice(input), interner),
+                Emitted::Empty { .. } => {
+                    InternedToken::from_slice(TokenType::JSXText, "", interner)
+                }
+                Emitted::Punct { token_type, span } => {
+                    InternedToken::from_slice(token_type, span.slice(input), interner)
+                }
+            };
+            tokens.push(token);
+            ControlFlow::Continue(())
+        })
+        .map_err(|(error, _)| error)
+        .map(|_| tokens)
+}
@@ -0,0 +1,69 @@
+use crate::tokenizer::{Token, Tokenizer, TokenizerError};
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::str::Chars;
+
+/// Lazy token iterator that runs the state machine only as far as needed
+///
+/// Each call to `next` consumes characters until the next token is complete.
+/// After an error is yielded the iterator is exhausted.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_iter, TokenType};
+///
+/// // Only the first tag is lexed; the malformed tail is never reached
+/// let name = tokenize_iter("<section>...<@@@")
+///     .find_map(|token| match token {
+///         Ok(token) if token.token_type == TokenType::JSXIdentifier => Some(token.value),
+///         _ => None,
+///     });
+/// assert_eq!(name.as_deref(), Some("section"));
+/// ```
+pub struct Tokens<'a> {
+    chars: Chars<'a>,
+    tokenizer: Tokenizer,
+    pending: VecDeque<Token>,
+    done: bool,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars(),
+            tokenizer: Tokenizer::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Result<Token, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(Ok(token));
+            }
+            if self.done {
+                return None;
+            }
+            let Some(ch) = self.chars.next() else {
+                self.done = true;
+                return None;
+            };
+            if let Err(error) = self.tokenizer.process_char(ch) {
+                self.done = true;
+                return Some(Err(error));
+            }
+            self.pending.extend(self.tokenizer.drain_tokens());
+        }
+    }
+}
+
+impl FusedIterator for Tokens<'_> {}
+
+/// Tokenize JSX lazily, yielding tokens as they are recognized
+pub fn tokenize_iter(input: &str) -> Tokens<'_> {
+    Tokens::new(input)
+}
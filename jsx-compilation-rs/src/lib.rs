@@ -49,16 +49,76 @@
 //! - `InvalidFirstCharacter`: Input doesn't start with `<`
 //! - `UnexpectedCharacter`: Invalid character in the current context
 //! - `UnexpectedEndOfInput`: Incomplete JSX structure
+//!
+//! Error messages are available in English and Chinese through [`Locale`]:
+//!
+//! ```rust
+//! use jsx_compilation_rs::{tokenizer, Locale};
+//!
+//! let err = tokenizer("invalid").unwrap_err();
+//! assert_eq!(err.to_string(), "First character must be <");
+//! assert_eq!(err.message(Locale::ZhCn), "第一个字符必须是<");
+//! ```
 
+pub mod arithmetic;
+pub mod ast;
+pub mod batch;
+pub mod budget;
+pub mod dfa;
+pub mod diagnostic;
+pub mod encoding;
+pub mod expression;
+pub mod frame;
+pub mod incremental;
+pub mod intern;
+pub mod iter;
+pub mod locale;
+pub mod nesting;
+pub mod parser;
+pub mod push;
+pub mod reader;
+pub mod roles;
+pub mod sax;
+pub mod span;
+pub mod token_reader;
 pub mod tokenizer;
+pub mod validate;
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
 
-pub use tokenizer::{tokenizer, Token, TokenType, TokenizerError};
+pub use batch::{
+    tokenize_batch, BatchError, BatchInput, BatchOptions, BatchReport, BatchResult, BatchSource,
+    BatchStats,
+};
+pub use budget::{tokenize_budgeted, Budget, Paused, Progress};
+pub use diagnostic::{apply_suggestions, fix, suggest_fixes, Diagnostic, FixKind, Suggestion};
+pub use encoding::{
+    decode, tokenize_bytes, Decoded, DecodedTokens, Encoding, InvalidBytes, UnknownEncoding,
+};
+pub use expression::{parse_expression, ExpressionError};
+pub use frame::{render_code_frame, FrameOptions};
+pub use incremental::{retokenize, Retokenized, TextEdit};
+pub use intern::{tokenize_interned, InternedToken, InternedValue, Interner, Symbol};
+pub use iter::{tokenize_iter, Tokens};
+pub use locale::{Locale, LocalizedError, UnknownLocale};
+pub use nesting::{check_nesting, nesting_errors, NestingError};
+pub use parser::{parse, ParseError};
+pub use push::PushTokenizer;
+pub use reader::{tokenize_reader, ReadError, ReaderTokens};
+pub use roles::{assign_roles, tokenize_roles, RoleToken, TokenRole};
+pub use sax::{tokenize_events, AttributeValue, JsxHandler};
+pub use span::{normalize_line_endings, Position, Span, SpannedToken};
+pub use token_reader::{AsToken, ReaderCheckpoint, TokenReader, UnexpectedToken};
+pub use tokenizer::{
+    tokenize_spanned, tokenizer, Checkpoint, ErrorReport, State, Token, TokenType, Tokenizer,
+    TokenizerError,
+};
+pub use validate::validate;
 
 /// Convenience function to tokenize JSX and return JSON string
 ///
@@ -80,9 +140,28 @@ pub fn tokenize_to_json(input: &str) -> Result<String, Box<dyn std::error::Error
     Ok(json)
 }
 
+/// Convenience function to serialize a tokenizer error to JSON
+///
+/// The JSON carries a stable `code` (e.g. `JSX0002`) that tools can match on
+/// instead of the message text, which may change or be localized.
+///
+/// # Example
+///
+/// ```rust
+/// use jsx_compilation_rs::{error_to_json, tokenizer, Locale};
+///
+/// let err = tokenizer("<div@>").unwrap_err();
+/// let json = error_to_json(&err, Locale::En).unwrap();
+/// assert!(json.contains(r#""code": "JSX0002""#));
+/// ```
+pub fn error_to_json(error: &TokenizerError, locale: Locale) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&error.report(locale))
+}
+
 /// Convenience function to check if JSX syntax is valid
 ///
 /// Returns `true` if the JSX can be tokenized without errors, `false` otherwise.
+/// Runs [`validate`], so no tokens are built.
 ///
 /// # Example
 ///
@@ -93,5 +172,5 @@ pub fn tokenize_to_json(input: &str) -> Result<String, Box<dyn std::error::Error
 /// assert!(!is_valid_jsx(r#"invalid"#)); // Doesn't start with <
 /// ```
 pub fn is_valid_jsx(input: &str) -> bool {
-    tokenizer(input).is_ok()
+    validate(input).is_ok()
 }
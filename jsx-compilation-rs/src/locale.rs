@@ -0,0 +1,189 @@
+use crate::diagnostic::FixKind;
+use crate::encoding::InvalidBytes;
+use crate::nesting::NestingError;
+use crate::tokenizer::TokenizerError;
+
+/// Languages available in the error message catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    /// English (`en`)
+    #[default]
+    En,
+    /// Simplified Chinese (`zh-CN`)
+    ZhCn,
+}
+
+impl Locale {
+    /// All locales supported by the catalog
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::ZhCn];
+
+    /// BCP 47 language tag for this locale
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    /// Look up the message for a tokenizer error in this locale
+    pub fn format_error(&self, error: &TokenizerError) -> String {
+        match (self, error) {
+            (Locale::En, TokenizerError::InvalidFirstCharacter) => {
+                "First character must be <".to_string()
+            }
+            (Locale::En, TokenizerError::UnexpectedCharacter(ch)) => {
+                format!("Unexpected character: {}", ch)
+            }
+            (Locale::En, TokenizerError::UnexpectedEndOfInput) => {
+                "Unexpected end of input".to_string()
+            }
+            (Locale::ZhCn, TokenizerError::InvalidFirstCharacter) => {
+                "第一个字符必须是<".to_string()
+            }
+            (Locale::ZhCn, TokenizerError::UnexpectedCharacter(ch)) => {
+                format!("意外的字符: {}", ch)
+            }
+            (Locale::ZhCn, TokenizerError::UnexpectedEndOfInput) => "意外的输入结束".to_string(),
+        }
+    }
+
+    /// Look up the message for an invalid byte sequence in this locale
+    pub fn format_invalid_bytes(&self, invalid: &InvalidBytes) -> String {
+        match self {
+            Locale::En => format!(
+                "Invalid {} byte sequence at byte {}",
+                invalid.encoding, invalid.span.start
+            ),
+            Locale::ZhCn => format!(
+                "第 {} 字节处的 {} 字节序列无效",
+                invalid.span.start, invalid.encoding
+            ),
+        }
+    }
+
+    /// Look up the message for a nesting error in this locale
+    pub fn format_nesting(&self, error: &NestingError) -> String {
+        match (self, error) {
+            (
+                Locale::En,
+                NestingError::MismatchedClosingTag {
+                    expected, found, ..
+                },
+            ) => format!("Expected </{}> but found </{}>", expected, found),
+            (Locale::En, NestingError::StrayClosingTag { name, .. }) => {
+                format!("Closing tag </{}> has no matching opening tag", name)
+            }
+            (Locale::En, NestingError::UnclosedElement { name, .. }) => {
+                format!("Element <{}> is never closed", name)
+            }
+            (
+                Locale::ZhCn,
+                NestingError::MismatchedClosingTag {
+                    expected, found, ..
+                },
+            ) => format!("应为 </{}>，但找到了 </{}>", expected, found),
+            (Locale::ZhCn, NestingError::StrayClosingTag { name, .. }) => {
+                format!("闭合标签 </{}> 没有对应的开始标签", name)
+            }
+            (Locale::ZhCn, NestingError::UnclosedElement { name, .. }) => {
+                format!("元素 <{}> 未闭合", name)
+            }
+        }
+    }
+
+    /// Look up the label pointing at the opening tag of an element
+    pub fn format_opened_here(&self, name: &str) -> String {
+        match self {
+            Locale::En => format!("<{}> is opened here", name),
+            Locale::ZhCn => format!("<{}> 在此处开始", name),
+        }
+    }
+
+    /// Look up the description of a fix-it suggestion in this locale
+    pub fn format_fix(&self, fix: &FixKind) -> String {
+        match (self, fix) {
+            (Locale::En, FixKind::QuoteAttributeValue) => {
+                "Wrap the attribute value in double quotes".to_string()
+            }
+            (Locale::En, FixKind::UseDoubleQuotes) => {
+                "Use double quotes for attribute values".to_string()
+            }
+            (Locale::En, FixKind::RemoveWhitespace) => "Remove the extra whitespace".to_string(),
+            (Locale::En, FixKind::ExpandSelfClosing { name }) => {
+                format!(
+                    "Self-closing tags are not supported; close <{}> explicitly",
+                    name
+                )
+            }
+            (Locale::En, FixKind::RenameAttribute { from, to }) => {
+                format!("Use {} instead of {} in JSX", to, from)
+            }
+            (Locale::En, FixKind::CloseElement { name }) => {
+                format!("Close the <{}> element", name)
+            }
+            (Locale::ZhCn, FixKind::QuoteAttributeValue) => "用双引号包裹属性值".to_string(),
+            (Locale::ZhCn, FixKind::UseDoubleQuotes) => "属性值请使用双引号".to_string(),
+            (Locale::ZhCn, FixKind::RemoveWhitespace) => "删除多余的空白".to_string(),
+            (Locale::ZhCn, FixKind::ExpandSelfClosing { name }) => {
+                format!("不支持自闭合标签，请显式闭合 <{}>", name)
+            }
+            (Locale::ZhCn, FixKind::RenameAttribute { from, to }) => {
+                format!("JSX 中请使用 {} 代替 {}", to, from)
+            }
+            (Locale::ZhCn, FixKind::CloseElement { name }) => {
+                format!("闭合 <{}> 元素", name)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tag())
+    }
+}
+
+/// Error returned when parsing an unsupported locale tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLocale(pub String);
+
+impl std::fmt::Display for UnknownLocale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unsupported locale: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownLocale {}
+
+impl std::str::FromStr for Locale {
+    type Err = UnknownLocale;
+
+    /// Parse a language tag such as `en`, `en-US`, `zh-CN` or `zh_CN` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().replace('_', "-").to_ascii_lowercase();
+        match tag.as_str() {
+            "en" | "en-us" | "en-gb" => Ok(Locale::En),
+            "zh" | "zh-cn" | "zh-hans" | "zh-hans-cn" => Ok(Locale::ZhCn),
+            _ => Err(UnknownLocale(s.to_string())),
+        }
+    }
+}
+
+/// A tokenizer error paired with the locale it should be displayed in
+#[derive(Debug, Clone, Copy)]
+pub struct LocalizedError<'a> {
+    error: &'a TokenizerError,
+    locale: Locale,
+}
+
+impl<'a> LocalizedError<'a> {
+    pub fn new(error: &'a TokenizerError, locale: Locale) -> Self {
+        Self { error, locale }
+    }
+}
+
+impl std::fmt::Display for LocalizedError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.locale.format_error(self.error))
+    }
+}
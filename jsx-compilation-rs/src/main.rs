@@ -1,4 +1,6 @@
-use jsx_compilation_rs::{tokenizer, tokenize_to_json, is_valid_jsx};
+use jsx_compilation_rs::{
+    is_valid_jsx, tokenize_spanned, tokenize_to_json, tokenizer, FrameOptions, Locale,
+};
 
 fn main() {
     // Example usage
@@ -31,4 +33,14 @@ fn main() {
     let invalid_jsx = "invalid";
     println!("Input: {}", invalid_jsx);
     println!("Is valid: {}", is_valid_jsx(invalid_jsx));
+
+    // Test error rendering
+    println!("\nRendering an error:");
+    let broken_jsx = "<div>\n  <h1 id=title>hello</h1>\n</div>";
+    if let Err(diagnostic) = tokenize_spanned(broken_jsx) {
+        eprint!(
+            "{}",
+            diagnostic.render(broken_jsx, Locale::En, FrameOptions::default())
+        );
+    }
 }
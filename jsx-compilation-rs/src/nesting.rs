@@ -0,0 +1,172 @@
+use crate::diagnostic::Diagnostic;
+use crate::locale::Locale;
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::{tokenize_spanned, TokenType};
+use serde::{Deserialize, Serialize};
+
+/// A tag that does not fit the element structure around it
+///
+/// The tokenizer reads each tag on its own, so these are only found by
+/// [`check_nesting`]. Spans cover whole tags, from `<` to `>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum NestingError {
+    /// `<div><span></div>`: a closing tag for an element other than the innermost one
+    MismatchedClosingTag {
+        expected: String,
+        found: String,
+        /// The opening tag of the innermost element
+        opening: Span,
+        /// The closing tag that does not match it
+        closing: Span,
+    },
+    /// `</span>` with no open `<span>` to close
+    StrayClosingTag { name: String, span: Span },
+    /// An element still open at the end of input; the span is its opening tag
+    UnclosedElement { name: String, span: Span },
+}
+
+impl NestingError {
+    /// Stable code, alongside the [`TokenizerError`](crate::TokenizerError) codes
+    pub fn code(&self) -> &'static str {
+        match self {
+            NestingError::MismatchedClosingTag { .. } => "JSX0005",
+            NestingError::StrayClosingTag { .. } => "JSX0006",
+            NestingError::UnclosedElement { .. } => "JSX0007",
+        }
+    }
+
+    /// Where the error is reported; for a mismatch, the wrong closing tag
+    pub fn span(&self) -> Span {
+        match self {
+            NestingError::MismatchedClosingTag { closing, .. } => *closing,
+            NestingError::StrayClosingTag { span, .. }
+            | NestingError::UnclosedElement { span, .. } => *span,
+        }
+    }
+
+    /// Message in the given locale
+    pub fn message(&self, locale: Locale) -> String {
+        locale.format_nesting(self)
+    }
+}
+
+impl std::fmt::Display for NestingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for NestingError {}
+
+/// An element that has been opened and not yet closed
+struct OpenElement<'a> {
+    name: &'a str,
+    span: Span,
+}
+
+/// Check that every closing tag matches the innermost open element
+///
+/// Walks the tokens with a stack of open elements. A closing tag for an
+/// element further down the stack is reported as a mismatch against the
+/// innermost element, and closes everything above it; elements skipped over
+/// besides the innermost one are reported as unclosed. A closing tag for an
+/// element that is not open at all is stray and leaves the stack alone.
+/// Elements left on the stack at the end are unclosed. Errors are returned in
+/// source order of the tags that reveal them.
+pub fn nesting_errors(tokens: &[SpannedToken]) -> Vec<NestingError> {
+    let mut errors = Vec::new();
+    let mut open_elements: Vec<OpenElement> = Vec::new();
+    let mut tag_start = 0;
+    let mut tag: Option<(&str, bool)> = None;
+    let mut self_closing = false;
+
+    for (i, spanned) in tokens.iter().enumerate() {
+        let token = &spanned.token;
+        match token.token_type {
+            TokenType::LeftParentheses => {
+                tag_start = spanned.span.start;
+                self_closing = false;
+            }
+            // `/` after the name is the start of `/>`, when self-closing tags are enabled
+            TokenType::BackSlash => self_closing = tag.is_some(),
+            TokenType::JSXIdentifier => {
+                let closing = i > 0 && tokens[i - 1].token.token_type == TokenType::BackSlash;
+                tag = Some((&token.value, closing));
+            }
+            TokenType::RightParentheses => {
+                let Some((name, closing)) = tag.take() else {
+                    continue;
+                };
+                let span = Span::new(tag_start, spanned.span.end);
+                if self_closing {
+                    continue;
+                }
+                if !closing {
+                    open_elements.push(OpenElement { name, span });
+                    continue;
+                }
+                match open_elements.iter().rposition(|open| open.name == name) {
+                    None => errors.push(NestingError::StrayClosingTag {
+                        name: name.to_string(),
+                        span,
+                    }),
+                    Some(index) => {
+                        let innermost = open_elements.len() - 1;
+                        if index < innermost {
+                            let open = &open_elements[innermost];
+                            errors.push(NestingError::MismatchedClosingTag {
+                                expected: open.name.to_string(),
+                                found: name.to_string(),
+                                opening: open.span,
+                                closing: span,
+                            });
+                            for skipped in open_elements[index + 1..innermost].iter().rev() {
+                                errors.push(unclosed(skipped));
+                            }
+                        }
+                        open_elements.truncate(index);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    errors.extend(open_elements.iter().rev().map(unclosed));
+    errors
+}
+
+fn unclosed(open: &OpenElement) -> NestingError {
+    NestingError::UnclosedElement {
+        name: open.name.to_string(),
+        span: open.span,
+    }
+}
+
+/// Tokenize JSX and check its element structure
+///
+/// Fails as [`tokenize_spanned`] does on input that cannot be tokenized;
+/// otherwise returns the [`nesting_errors`], empty when every element is
+/// closed by a matching tag.
+///
+/// ```rust
+/// use jsx_compilation_rs::{check_nesting, NestingError, Span};
+///
+/// assert_eq!(check_nesting("<div><span></span></div>").unwrap(), vec![]);
+///
+/// let errors = check_nesting("<div><span></div>").unwrap();
+/// assert_eq!(
+///     errors,
+///     vec![NestingError::MismatchedClosingTag {
+///         expected: "span".to_string(),
+///         found: "div".to_string(),
+///         opening: Span::new(5, 11),
+///         closing: Span::new(11, 17),
+///     }]
+/// );
+/// assert_eq!(errors[0].to_string(), "Expected </span> but found </div>");
+/// ```
+pub fn check_nesting(input: &str) -> Result<Vec<NestingError>, Diagnostic> {
+    Ok(nesting_errors(&tokenize_spanned(input)?))
+}
@@ -0,0 +1,339 @@
+use crate::ast::{
+    JSXAttribute, JSXAttributeItem, JSXAttributeValue, JSXChild, JSXElement,
+    JSXExpressionContainer, JSXText, StringLiteral,
+};
+use crate::diagnostic::Diagnostic;
+use crate::expression::{container_end, parse_expression_in, ExpressionError};
+use crate::nesting::{nesting_errors, NestingError};
+use crate::span::{Span, SpannedToken};
+use crate::token_reader::TokenReader;
+use crate::tokenizer::{State, TokenType, Tokenizer};
+use serde::{Deserialize, Serialize};
+
+/// Why [`parse`] failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ParseError {
+    /// The input could not be tokenized
+    Syntax { diagnostic: Diagnostic },
+    /// A token that cannot appear at this point of the tree
+    UnexpectedToken { token_type: TokenType, span: Span },
+    /// The input ended inside a tag, or was empty
+    UnexpectedEndOfInput { span: Span },
+    /// Tags that are not properly nested; the first one found
+    Nesting { error: NestingError },
+    /// A second element after the root element
+    AdjacentElements { span: Span },
+    /// The JavaScript in an expression container could not be parsed
+    Expression { error: ExpressionError },
+}
+
+impl ParseError {
+    /// Where the error is reported; for a mismatch, the wrong closing tag
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::Syntax { diagnostic } => diagnostic.span,
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEndOfInput { span }
+            | ParseError::AdjacentElements { span } => *span,
+            ParseError::Nesting { error } => error.span(),
+            ParseError::Expression { error } => error.span(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Syntax { diagnostic } => write!(f, "{}", diagnostic),
+            ParseError::UnexpectedToken { token_type, .. } => {
+                write!(f, "Unexpected {} token", token_type)
+            }
+            ParseError::UnexpectedEndOfInput { .. } => write!(f, "Unexpected end of input"),
+            ParseError::Nesting { error } => write!(f, "{}", error),
+            ParseError::Expression { error } => write!(f, "{}", error),
+            ParseError::AdjacentElements { .. } => {
+                write!(f, "Adjacent elements must be wrapped in a parent element")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<Diagnostic> for ParseError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        ParseError::Syntax { diagnostic }
+    }
+}
+
+impl From<ExpressionError> for ParseError {
+    fn from(error: ExpressionError) -> Self {
+        ParseError::Expression { error }
+    }
+}
+
+impl From<NestingError> for ParseError {
+    fn from(error: NestingError) -> Self {
+        ParseError::Nesting { error }
+    }
+}
+
+/// Recursive descent over the spanned token stream
+struct Parser<'a> {
+    reader: TokenReader<'a, SpannedToken>,
+    /// The input, for parsing the code in expression containers
+    source: &'a str,
+    /// Spans of the expression containers among children, in source order
+    containers: &'a [Span],
+}
+
+impl<'a> Parser<'a> {
+    /// A parser over `tokens` of `source`, once they are known to be properly nested
+    fn new(
+        source: &'a str,
+        tokens: &'a [SpannedToken],
+        containers: &'a [Span],
+    ) -> Result<Self, ParseError> {
+        if let Some(error) = nesting_errors(tokens).into_iter().next() {
+            return Err(error.into());
+        }
+        Ok(Self {
+            reader: TokenReader::new(tokens),
+            source,
+            containers,
+        })
+    }
+
+    /// The next child expression container, if it comes before the next token
+    fn container(&mut self) -> Option<Span> {
+        let span = *self.containers.first()?;
+        if self
+            .reader
+            .peek()
+            .is_some_and(|next| next.span.start < span.start)
+        {
+            return None;
+        }
+        self.containers = &self.containers[1..];
+        Some(span)
+    }
+
+    /// `{ expression }` spanning `span`, braces included
+    fn expression_container(&self, span: Span) -> Result<JSXExpressionContainer, ParseError> {
+        let code = Span::new(span.start + 1, span.end - 1);
+        Ok(JSXExpressionContainer {
+            expression: parse_expression_in(self.source, code)?,
+            span,
+        })
+    }
+
+    /// Consume a token of type `expected`
+    fn expect(&mut self, expected: TokenType) -> Result<&'a SpannedToken, ParseError> {
+        self.reader
+            .expect(expected)
+            .map_err(|error| match (error.found, error.span) {
+                (Some(token_type), Some(span)) => ParseError::UnexpectedToken { token_type, span },
+                _ => self.end_of_input(),
+            })
+    }
+
+    fn end_of_input(&self) -> ParseError {
+        ParseError::UnexpectedEndOfInput {
+            span: Span::new(self.source.len(), self.source.len()),
+        }
+    }
+
+    /// `< name attributes > children </ name >` or `< name attributes / >`
+    fn element(&mut self) -> Result<JSXElement, ParseError> {
+        let start = self.expect(TokenType::LeftParentheses)?.span.start;
+        let name = self.expect(TokenType::JSXIdentifier)?;
+        let mut attributes = Vec::new();
+        while self.reader.peek_type(0) == Some(TokenType::AttributeKey) {
+            attributes.push(JSXAttributeItem::JSXAttribute(self.attribute()?));
+        }
+        if self.reader.peek_type(0) == Some(TokenType::BackSlash) {
+            self.expect(TokenType::BackSlash)?;
+            let end = self.expect(TokenType::RightParentheses)?.span.end;
+            return Ok(JSXElement {
+                name: name.token.value.clone(),
+                attributes,
+                children: Vec::new(),
+                span: Span::new(start, end),
+                opening_span: Span::new(start, end),
+                closing_span: None,
+            });
+        }
+        let opening_span = Span::new(start, self.expect(TokenType::RightParentheses)?.span.end);
+
+        let mut children = Vec::new();
+        loop {
+            if let Some(span) = self.container() {
+                children.push(JSXChild::JSXExpressionContainer(
+                    self.expression_container(span)?,
+                ));
+                continue;
+            }
+            match self.reader.peek_type(0) {
+                Some(TokenType::JSXText) => {
+                    let text = self.reader.read().expect("peeked");
+                    children.push(JSXChild::JSXText(JSXText {
+                        value: text.token.value.clone(),
+                        span: text.span,
+                    }));
+                }
+                Some(TokenType::LeftParentheses)
+                    if self.reader.peek_type(1) == Some(TokenType::BackSlash) =>
+                {
+                    break;
+                }
+                Some(_) => children.push(JSXChild::JSXElement(self.element()?)),
+                None => return Err(self.end_of_input()),
+            }
+        }
+
+        // Nesting was checked up front, so the closing tag names this element
+        let closing_start = self.expect(TokenType::LeftParentheses)?.span.start;
+        self.expect(TokenType::BackSlash)?;
+        self.expect(TokenType::JSXIdentifier)?;
+        let closing_end = self.expect(TokenType::RightParentheses)?.span.end;
+        let closing_span = Span::new(closing_start, closing_end);
+
+        Ok(JSXElement {
+            name: name.token.value.clone(),
+            attributes,
+            children,
+            span: Span::new(start, closing_end),
+            opening_span,
+            closing_span: Some(closing_span),
+        })
+    }
+
+    /// `key="value"` or `key={expression}`
+    fn attribute(&mut self) -> Result<JSXAttribute, ParseError> {
+        let key = self.expect(TokenType::AttributeKey)?;
+        let value = self.reader.read().ok_or_else(|| self.end_of_input())?;
+        let node = match value.token.token_type {
+            TokenType::AttributeStringValue => JSXAttributeValue::StringLiteral(StringLiteral {
+                value: value.token.value.clone(),
+                span: value.span,
+            }),
+            TokenType::AttributeExpressionValue => {
+                JSXAttributeValue::JSXExpressionContainer(self.expression_container(value.span)?)
+            }
+            token_type => {
+                return Err(ParseError::UnexpectedToken {
+                    token_type,
+                    span: value.span,
+                })
+            }
+        };
+        Ok(JSXAttribute {
+            name: key.token.value.clone(),
+            value: Some(node),
+            span: Span::new(key.span.start, value.span.end),
+        })
+    }
+}
+
+/// Parse JSX into a tree rooted at its outermost element
+///
+/// Built on the same tokens as [`tokenizer`](crate::tokenizer()), but stricter:
+/// every element must be closed by a matching tag, as checked by
+/// [`nesting_errors`], and only one root element is allowed. Text after the
+/// root element is ignored, as the tokenizer drops it. Expression containers
+/// are the exception where `parse` accepts more: the tokenizer takes only
+/// letters and digits between the braces of an attribute and reads braces
+/// among children as text, while `parse` takes any expression
+/// [`parse_expression`](crate::parse_expression) does in both places, as in
+/// `onClick={() => select(item.id)}` or `<ul>{items.map(item => <li/>)}</ul>`.
+///
+/// ```rust
+/// use jsx_compilation_rs::ast::{JSXAttributeValue, JSXChild};
+/// use jsx_compilation_rs::{parse, Span};
+///
+/// let root = parse(r#"<ul id="list"><li>one</li></ul>"#).unwrap();
+/// assert_eq!(root.name, "ul");
+/// assert!(matches!(
+///     &root.attributes[0],
+///     jsx_compilation_rs::ast::JSXAttributeItem::JSXAttribute(a)
+///         if matches!(&a.value, Some(JSXAttributeValue::StringLiteral(s)) if s.value == "list")
+/// ));
+/// let JSXChild::JSXElement(li) = &root.children[0] else { panic!() };
+/// assert_eq!(li.span, Span::new(14, 26));
+///
+/// let json = serde_json::to_value(&root).unwrap();
+/// assert_eq!(json["type"], "JSXElement");
+/// assert_eq!(json["children"][0]["children"][0]["type"], "JSXText");
+/// ```
+pub fn parse(input: &str) -> Result<JSXElement, ParseError> {
+    let (tokens, containers) = lex(input, 0, false)?;
+    let mut parser = Parser::new(input, &tokens, &containers)?;
+    let root = parser.element()?;
+    if let Some(next) = parser.reader.peek() {
+        return Err(ParseError::AdjacentElements { span: next.span });
+    }
+    Ok(root)
+}
+
+/// Parse the JSX element starting at byte `start` of `source`, ignoring what follows it
+///
+/// Used for JSX inside expressions, where self-closing tags are allowed.
+pub(crate) fn parse_element_at(source: &str, start: usize) -> Result<JSXElement, ParseError> {
+    let (tokens, containers) = lex(source, start, true)?;
+    Parser::new(source, &tokens, &containers)?.element()
+}
+
+/// Tokenize JSX from byte `start` of `source` for the parser
+///
+/// The tokenizer only takes letters and digits between the braces of an
+/// attribute expression, so the code there is parsed to find its closing `}`
+/// and handed to the tokenizer whole. An expression container among children
+/// is skipped the same way and returned by span next to the tokens, as there
+/// is no token for it. With `embedded`, self-closing tags are accepted and
+/// lexing stops at the `>` that closes the first element.
+fn lex(
+    source: &str,
+    start: usize,
+    embedded: bool,
+) -> Result<(Vec<SpannedToken>, Vec<Span>), ParseError> {
+    let mut tokenizer = if embedded {
+        Tokenizer::embedded(start)
+    } else {
+        Tokenizer::new()
+    };
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+    let mut containers = Vec::new();
+    let mut offset = start;
+    while let Some(ch) = source[offset..].chars().next() {
+        let between_tags = matches!(
+            tokenizer.state(),
+            State::JSXText | State::FoundRightParentheses
+        );
+        if ch == '{' && between_tags && tokenizer.depth() > 0 {
+            let end = container_end(source, offset + 1)? + 1;
+            tokenizer.skip_between_tags(end - offset);
+            tokens.extend(tokenizer.drain_spanned());
+            containers.push(Span::new(offset, end));
+            offset = end;
+            continue;
+        }
+        tokenizer.process_char_diagnosed(source, ch)?;
+        offset += ch.len_utf8();
+        // Only the `{` of an attribute expression leads into this state here
+        if tokenizer.state() == State::AttributeExpressionValue {
+            let end = container_end(source, offset)?;
+            tokenizer.push_raw(&source[offset..end]);
+            offset = end;
+        }
+        let emitted = tokens.len();
+        tokens.extend(tokenizer.drain_spanned());
+        let tag_closed = tokens[emitted..]
+            .last()
+            .is_some_and(|spanned| spanned.token.token_type == TokenType::RightParentheses);
+        if embedded && tag_closed && tokenizer.depth() == 0 {
+            break;
+        }
+    }
+    Ok((tokens, containers))
+}
@@ -0,0 +1,100 @@
+use crate::tokenizer::{Checkpoint, Token, Tokenizer, TokenizerError};
+
+/// Push-style tokenizer for input that arrives in chunks
+///
+/// State and any partially read token are kept between calls to
+/// [`feed`](PushTokenizer::feed), so chunks may split the input anywhere,
+/// including in the middle of a token. Once an error occurs every later call
+/// returns it again.
+///
+/// Unlike [`tokenizer`](crate::tokenizer), [`finish`](PushTokenizer::finish)
+/// checks that the input does not stop inside a tag and emits text that
+/// follows the last tag instead of dropping it.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenizer, PushTokenizer};
+///
+/// let mut push = PushTokenizer::new();
+/// let mut tokens = Vec::new();
+/// for chunk in ["<h1 i", "d=\"ti", "tle\">Hel", "lo</h", "1>"] {
+///     tokens.extend(push.feed(chunk).unwrap());
+/// }
+/// tokens.extend(push.finish().unwrap());
+/// assert_eq!(tokens, tokenizer(r#"<h1 id="title">Hello</h1>"#).unwrap());
+/// ```
+pub struct PushTokenizer {
+    tokenizer: Tokenizer,
+    error: Option<TokenizerError>,
+}
+
+impl Default for PushTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushTokenizer {
+    pub fn new() -> Self {
+        Self {
+            tokenizer: Tokenizer::new(),
+            error: None,
+        }
+    }
+
+    /// Feed the next chunk, returning the tokens it completed
+    pub fn feed(&mut self, chunk: &str) -> Result<Vec<Token>, TokenizerError> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        if let Err(error) = self.tokenizer.process_str(chunk) {
+            self.error = Some(error.clone());
+            return Err(error);
+        }
+        Ok(self.tokenizer.drain_tokens().collect())
+    }
+
+    /// Signal the end of input, returning any final tokens
+    pub fn finish(mut self) -> Result<Vec<Token>, TokenizerError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.tokenizer.finish()?;
+        Ok(self.tokenizer.drain_tokens().collect())
+    }
+
+    /// Snapshot the progress so far; see [`resume`](PushTokenizer::resume)
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.tokenizer.checkpoint()
+    }
+
+    /// Continue from a checkpoint; feed the input from
+    /// [`Checkpoint::offset`] onwards
+    ///
+    /// ```rust
+    /// use jsx_compilation_rs::{Checkpoint, PushTokenizer};
+    ///
+    /// let source = r#"<ul><li class="a">one</li></ul>"#;
+    /// let mut push = PushTokenizer::new();
+    /// push.feed(&source[..12]).unwrap();
+    /// let saved = serde_json::to_string(&push.checkpoint()).unwrap();
+    ///
+    /// // Later, possibly in another process
+    /// let checkpoint: Checkpoint = serde_json::from_str(&saved).unwrap();
+    /// assert_eq!(checkpoint.depth(), 1);
+    /// let offset = checkpoint.offset();
+    /// let mut push = PushTokenizer::resume(checkpoint);
+    /// let rest = push.feed(&source[offset..]).unwrap();
+    /// assert_eq!(rest[0].value, "class");
+    /// ```
+    pub fn resume(checkpoint: Checkpoint) -> Self {
+        Self {
+            tokenizer: Tokenizer::from_checkpoint(checkpoint),
+            error: None,
+        }
+    }
+
+    /// Number of bytes consumed so far; on error, the offset of the offending character
+    pub fn offset(&self) -> usize {
+        self.tokenizer.offset()
+    }
+}
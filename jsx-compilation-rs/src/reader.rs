@@ -0,0 +1,215 @@
+use crate::tokenizer::{Token, Tokenizer, TokenizerError};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::iter::FusedIterator;
+
+/// Error produced while tokenizing from a reader
+#[derive(Debug)]
+pub enum ReadError {
+    /// The underlying reader failed
+    Io(std::io::Error),
+    /// The bytes at `offset` are not valid UTF-8
+    InvalidUtf8 { offset: usize },
+    /// The input was read but is not valid JSX; `offset` is the byte offset of the failure
+    Tokenizer {
+        error: TokenizerError,
+        offset: usize,
+    },
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "I/O error: {}", e),
+            ReadError::InvalidUtf8 { offset } => {
+                write!(f, "Invalid UTF-8 at byte {}", offset)
+            }
+            ReadError::Tokenizer { error, offset } => write!(f, "{} at byte {}", error, offset),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::InvalidUtf8 { .. } => None,
+            ReadError::Tokenizer { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+/// Length of the UTF-8 sequence introduced by a leading byte
+fn utf8_sequence_len(first: u8) -> usize {
+    match first {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
+    }
+}
+
+/// Token iterator that reads and decodes its input incrementally
+///
+/// Bytes are taken straight from the reader's buffer and decoded as UTF-8;
+/// a multi-byte character split across two reads is held back until it is
+/// complete. As with [`PushTokenizer`](crate::PushTokenizer), the end of
+/// input is validated and text after the last tag is emitted.
+///
+/// ```rust
+/// use jsx_compilation_rs::tokenize_reader;
+///
+/// let file = std::io::Cursor::new(r#"<p class="intro">你好</p>"#);
+/// let tokens: Vec<_> = tokenize_reader(file).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(tokens[5].value, "你好");
+/// ```
+pub struct ReaderTokens<R> {
+    reader: R,
+    tokenizer: Tokenizer,
+    /// Leading bytes of a character whose remaining bytes have not been read yet
+    partial: Vec<u8>,
+    /// Bytes consumed from the reader so far
+    bytes_read: usize,
+    pending: VecDeque<Token>,
+    /// Error to report once the tokens completed before it are delivered
+    error: Option<ReadError>,
+    done: bool,
+}
+
+impl<R: BufRead> ReaderTokens<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            tokenizer: Tokenizer::new(),
+            partial: Vec::with_capacity(4),
+            bytes_read: 0,
+            pending: VecDeque::new(),
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Feed decoded text to the tokenizer, queueing completed tokens
+    fn feed(
+        tokenizer: &mut Tokenizer,
+        pending: &mut VecDeque<Token>,
+        text: &str,
+    ) -> Result<(), ReadError> {
+        let result = tokenizer.process_str(text);
+        pending.extend(tokenizer.drain_tokens());
+        result.map_err(|error| ReadError::Tokenizer {
+            error,
+            offset: tokenizer.offset(),
+        })
+    }
+
+    /// Read and tokenize the next buffer; returns `false` once the reader is exhausted
+    fn read_chunk(&mut self) -> Result<bool, ReadError> {
+        let available = match self.reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => return Ok(true),
+            Err(e) => return Err(e.into()),
+        };
+        if available.is_empty() {
+            if !self.partial.is_empty() {
+                return Err(ReadError::InvalidUtf8 {
+                    offset: self.bytes_read - self.partial.len(),
+                });
+            }
+            return Ok(false);
+        }
+
+        let mut consumed = 0;
+        if !self.partial.is_empty() {
+            // Complete the character split across the previous read
+            let needed = utf8_sequence_len(self.partial[0]) - self.partial.len();
+            consumed = needed.min(available.len());
+            self.partial.extend_from_slice(&available[..consumed]);
+            if self.partial.len() == utf8_sequence_len(self.partial[0]) {
+                let start = self.bytes_read - (self.partial.len() - consumed);
+                match std::str::from_utf8(&self.partial) {
+                    Ok(text) => Self::feed(&mut self.tokenizer, &mut self.pending, text)?,
+                    Err(_) => return Err(ReadError::InvalidUtf8 { offset: start }),
+                }
+                self.partial.clear();
+            }
+        }
+
+        let rest = &available[consumed..];
+        let valid_len = match std::str::from_utf8(rest) {
+            Ok(_) => rest.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => {
+                let valid = std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default();
+                Self::feed(&mut self.tokenizer, &mut self.pending, valid)?;
+                return Err(ReadError::InvalidUtf8 {
+                    offset: self.bytes_read + consumed + e.valid_up_to(),
+                });
+            }
+        };
+        let text = std::str::from_utf8(&rest[..valid_len]).unwrap_or_default();
+        Self::feed(&mut self.tokenizer, &mut self.pending, text)?;
+        self.partial.extend_from_slice(&rest[valid_len..]);
+
+        let len = available.len();
+        self.reader.consume(len);
+        self.bytes_read += len;
+        Ok(true)
+    }
+
+    /// Validate the end of input and queue the final tokens
+    fn finish(&mut self) -> Result<(), ReadError> {
+        let result = self.tokenizer.finish();
+        self.pending.extend(self.tokenizer.drain_tokens());
+        result.map_err(|error| ReadError::Tokenizer {
+            error,
+            offset: self.tokenizer.offset(),
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderTokens<R> {
+    type Item = Result<Token, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(Ok(token));
+            }
+            if let Some(error) = self.error.take() {
+                return Some(Err(error));
+            }
+            if self.done {
+                return None;
+            }
+            let result = match self.read_chunk() {
+                Ok(true) => continue,
+                Ok(false) => {
+                    self.done = true;
+                    self.finish()
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                // Tokens completed before the failure are still delivered first
+                self.done = true;
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> FusedIterator for ReaderTokens<R> {}
+
+/// Tokenize JSX from any reader, decoding UTF-8 incrementally
+///
+/// Use [`ReaderTokens::new`] directly to reuse an existing `BufRead`.
+pub fn tokenize_reader<R: Read>(reader: R) -> ReaderTokens<BufReader<R>> {
+    ReaderTokens::new(BufReader::new(reader))
+}
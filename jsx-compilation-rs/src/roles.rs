@@ -0,0 +1,153 @@
+use crate::diagnostic::Diagnostic;
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::{TokenType, Tokenizer};
+use serde::{Deserialize, Serialize};
+
+/// What a token means in its tag, rather than which characters it was read from
+///
+/// Where `TokenType` reports a closing tag as `LeftParentheses, BackSlash,
+/// JSXIdentifier, RightParentheses`, roles report it as `ClosingTagStart,
+/// TagName, TagEnd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TokenRole {
+    /// `<` starting an opening or self-closing tag
+    OpeningTagStart,
+    /// `</` starting a closing tag
+    ClosingTagStart,
+    /// Element name in any tag
+    TagName,
+    /// Attribute name
+    AttributeName,
+    /// String attribute value, without quotes
+    AttributeStringValue,
+    /// Expression attribute value, without braces
+    AttributeExpressionValue,
+    /// `>` ending an opening or closing tag
+    TagEnd,
+    /// `/>` ending a self-closing tag
+    SelfClosingTagEnd,
+    /// Text content between tags
+    Text,
+}
+
+impl std::fmt::Display for TokenRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TokenRole::OpeningTagStart => "OpeningTagStart",
+            TokenRole::ClosingTagStart => "ClosingTagStart",
+            TokenRole::TagName => "TagName",
+            TokenRole::AttributeName => "AttributeName",
+            TokenRole::AttributeStringValue => "AttributeStringValue",
+            TokenRole::AttributeExpressionValue => "AttributeExpressionValue",
+            TokenRole::TagEnd => "TagEnd",
+            TokenRole::SelfClosingTagEnd => "SelfClosingTagEnd",
+            TokenRole::Text => "Text",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A token labelled with its role
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleToken {
+    pub role: TokenRole,
+    pub value: String,
+    pub span: Span,
+}
+
+impl RoleToken {
+    pub fn new(role: TokenRole, value: impl Into<String>, span: Span) -> Self {
+        Self {
+            role,
+            value: value.into(),
+            span,
+        }
+    }
+}
+
+/// Label a `TokenType` stream with roles, merging `<` `/` and `/` `>` pairs
+pub fn assign_roles(tokens: &[SpannedToken]) -> Vec<RoleToken> {
+    let mut roles = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let SpannedToken { token, span } = &tokens[i];
+        let next_is = |token_type: TokenType| {
+            tokens
+                .get(i + 1)
+                .is_some_and(|next| next.token.token_type == token_type)
+        };
+
+        let role = match token.token_type {
+            TokenType::LeftParentheses if next_is(TokenType::BackSlash) => {
+                // The reference grammar allows `<//a>`, so fold every slash into the start
+                let mut end = i + 1;
+                while end + 1 < tokens.len()
+                    && tokens[end + 1].token.token_type == TokenType::BackSlash
+                {
+                    end += 1;
+                }
+                let value: String = tokens[i..=end]
+                    .iter()
+                    .map(|t| t.token.value.as_str())
+                    .collect();
+                roles.push(RoleToken::new(
+                    TokenRole::ClosingTagStart,
+                    value,
+                    Span::new(span.start, tokens[end].span.end),
+                ));
+                i = end + 1;
+                continue;
+            }
+            TokenType::BackSlash if next_is(TokenType::RightParentheses) => {
+                roles.push(RoleToken::new(
+                    TokenRole::SelfClosingTagEnd,
+                    "/>",
+                    Span::new(span.start, tokens[i + 1].span.end),
+                ));
+                i += 2;
+                continue;
+            }
+            TokenType::LeftParentheses => TokenRole::OpeningTagStart,
+            TokenType::JSXIdentifier => TokenRole::TagName,
+            TokenType::AttributeKey => TokenRole::AttributeName,
+            TokenType::AttributeStringValue => TokenRole::AttributeStringValue,
+            TokenType::AttributeExpressionValue => TokenRole::AttributeExpressionValue,
+            TokenType::RightParentheses => TokenRole::TagEnd,
+            TokenType::JSXText => TokenRole::Text,
+            // A self-closing `/` cut off by the end of input
+            TokenType::BackSlash => TokenRole::SelfClosingTagEnd,
+        };
+        roles.push(RoleToken::new(role, token.value.clone(), *span));
+        i += 1;
+    }
+
+    roles
+}
+
+/// Tokenize JSX into role-labelled tokens
+///
+/// Unlike [`tokenizer`](crate::tokenizer), this mode accepts self-closing tags
+/// such as `<img/>`, reported as `SelfClosingTagEnd`.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_roles, TokenRole};
+///
+/// let roles: Vec<TokenRole> = tokenize_roles("<p><br/></p>")
+///     .unwrap()
+///     .into_iter()
+///     .map(|t| t.role)
+///     .collect();
+/// assert_eq!(
+///     roles,
+///     vec![
+///         TokenRole::OpeningTagStart, TokenRole::TagName, TokenRole::TagEnd,
+///         TokenRole::OpeningTagStart, TokenRole::TagName, TokenRole::SelfClosingTagEnd,
+///         TokenRole::ClosingTagStart, TokenRole::TagName, TokenRole::TagEnd,
+///     ]
+/// );
+/// ```
+pub fn tokenize_roles(input: &str) -> Result<Vec<RoleToken>, Diagnostic> {
+    let tokens = Tokenizer::with_self_closing().run_spanned(input)?;
+    Ok(assign_roles(&tokens))
+}
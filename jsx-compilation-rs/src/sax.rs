@@ -0,0 +1,172 @@
+use crate::dfa::{Emitted, Table};
+use crate::span::Span;
+use crate::tokenizer::{TokenType, TokenizerError};
+use std::ops::ControlFlow;
+
+/// Value of an attribute reported to [`JsxHandler::attribute`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributeValue<'a> {
+    /// `"value"`, without the quotes
+    String(&'a str),
+    /// `{code}`, without the braces
+    Expression(&'a str),
+}
+
+/// Receiver of the constructs found by [`tokenize_events`]
+///
+/// Every method does nothing by default, so a handler implements only the
+/// events it needs. Returning `ControlFlow::Break(())` stops lexing right
+/// after the construct being reported. Names and values borrow from the
+/// input; spans are byte ranges in it.
+pub trait JsxHandler {
+    /// The tag name of an opening tag, e.g. `div` in `<div class="a">`
+    fn open_element(&mut self, name: &str, span: Span) -> ControlFlow<()> {
+        let _ = (name, span);
+        ControlFlow::Continue(())
+    }
+
+    /// An attribute of the element last opened; `span` runs from the key to
+    /// the end of the value
+    fn attribute(&mut self, key: &str, value: AttributeValue<'_>, span: Span) -> ControlFlow<()> {
+        let _ = (key, value, span);
+        ControlFlow::Continue(())
+    }
+
+    /// The tag name of a closing tag, e.g. `div` in `</div>`
+    fn close_element(&mut self, name: &str, span: Span) -> ControlFlow<()> {
+        let _ = (name, span);
+        ControlFlow::Continue(())
+    }
+
+    /// Text between tags
+    fn text(&mut self, text: &str, span: Span) -> ControlFlow<()> {
+        let _ = (text, span);
+        ControlFlow::Continue(())
+    }
+
+    /// The code inside an expression container, without the braces
+    ///
+    /// Containers only appear as attribute values, which are also reported
+    /// to [`attribute`](Self::attribute), right after this call. Collecting
+    /// the identifiers a file references needs only this method.
+    fn expression(&mut self, code: &str, span: Span) -> ControlFlow<()> {
+        let _ = (code, span);
+        ControlFlow::Continue(())
+    }
+}
+
+/// State kept between tokens while reporting events
+struct Events<'a, H: ?Sized> {
+    input: &'a str,
+    handler: &'a mut H,
+    /// Key and start of the attribute being read
+    key: (&'a str, usize),
+    in_closing_tag: bool,
+}
+
+impl<'a, H: JsxHandler + ?Sized> Events<'a, H> {
+    /// Report the construct `emitted` completes, if any
+    fn emitted(&mut self, emitted: Emitted) -> ControlFlow<()> {
+        match emitted {
+            Emitted::Token {
+                token_type,
+                span,
+                value,
+            } => self.token(token_type, span, value.slice(self.input)),
+            Emitted::Empty { offset } => {
+                self.key = ("", offset);
+                ControlFlow::Continue(())
+            }
+            // Without self-closing tags, `/` only appears in closing tags
+            Emitted::Punct {
+                token_type: TokenType::BackSlash,
+                ..
+            } => {
+                self.in_closing_tag = true;
+                ControlFlow::Continue(())
+            }
+            Emitted::Punct {
+                token_type: TokenType::RightParentheses,
+                ..
+            } => {
+                self.in_closing_tag = false;
+                ControlFlow::Continue(())
+            }
+            Emitted::Punct { .. } => ControlFlow::Continue(()),
+        }
+    }
+
+    /// Report a text, name or value token
+    fn token(&mut self, token_type: TokenType, span: Span, value: &'a str) -> ControlFlow<()> {
+        match token_type {
+            TokenType::JSXIdentifier if self.in_closing_tag => {
+                self.handler.close_element(value, span)
+            }
+            TokenType::JSXIdentifier => self.handler.open_element(value, span),
+            TokenType::AttributeKey => {
+                self.key = (value, span.start);
+                ControlFlow::Continue(())
+            }
+            TokenType::AttributeStringValue => {
+                self.attribute(AttributeValue::String(value), span.end)
+            }
+            TokenType::AttributeExpressionValue => {
+                self.handler.expression(value, span)?;
+                self.attribute(AttributeValue::Expression(value), span.end)
+            }
+            TokenType::JSXText => self.handler.text(value, span),
+            TokenType::LeftParentheses | TokenType::RightParentheses | TokenType::BackSlash => {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    fn attribute(&mut self, value: AttributeValue<'_>, end: usize) -> ControlFlow<()> {
+        let (key, start) = self.key;
+        self.handler.attribute(key, value, Span::new(start, end))
+    }
+}
+
+/// Tokenize JSX, calling `handler` for each construct instead of building tokens
+///
+/// Accepts exactly the inputs [`tokenizer`](crate::tokenizer) accepts and
+/// reports the same constructs in source order, without allocating: names and
+/// values are slices of `input`. As with `tokenizer`, text after the last tag
+/// is not reported. Returns `Break` with the byte offset lexing stopped at if
+/// the handler asked to stop, and the error with its byte offset if the input
+/// is rejected.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenize_events, JsxHandler, Span};
+/// use std::ops::ControlFlow;
+///
+/// /// Finds the first element with the given name
+/// struct Find<'a>(&'a str, Option<Span>);
+///
+/// impl JsxHandler for Find<'_> {
+///     fn open_element(&mut self, name: &str, span: Span) -> ControlFlow<()> {
+///         if name == self.0 {
+///             self.1 = Some(span);
+///             return ControlFlow::Break(());
+///         }
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let mut find = Find("li", None);
+/// let flow = tokenize_events("<ul><li>one</li><@", &mut find).unwrap();
+/// assert_eq!(find.1, Some(Span::new(5, 7)));
+/// assert_eq!(flow, ControlFlow::Break(8));
+/// ```
+pub fn tokenize_events<H: JsxHandler + ?Sized>(
+    input: &str,
+    handler: &mut H,
+) -> Result<ControlFlow<usize>, (TokenizerError, usize)> {
+    let mut events = Events {
+        input,
+        handler,
+        key: ("", 0),
+        in_closing_tag: false,
+    };
+    Table::get(false).drive(input, |emitted| events.emitted(emitted))
+}
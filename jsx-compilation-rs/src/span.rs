@@ -0,0 +1,116 @@
+use crate::tokenizer::{Token, TokenType};
+use serde::{Deserialize, Serialize};
+
+/// A half-open byte range `start..end` in the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Number of bytes covered by the span
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Whether the span covers no bytes (an insertion point)
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The source text covered by the span
+    pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+/// A 1-based line and column in the source text; columns count characters
+///
+/// Lines end at `\n` or `\r\n`; the `\r` of a `\r\n` is part of the line
+/// terminator, so it never counts as a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Position of the byte `offset` in `source`
+    pub fn of(source: &str, offset: usize) -> Self {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let mut line = &before[line_start..];
+        if source[offset..].starts_with('\n') {
+            line = line.strip_suffix('\r').unwrap_or(line);
+        }
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: line.chars().count() + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A token together with the source span it was read from
+///
+/// Value tokens cover their delimiters, so the span of `AttributeStringValue`
+/// `title` read from `"title"` includes both quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpannedToken {
+    #[serde(flatten)]
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span }
+    }
+
+    /// The value exactly as written in `source`, without delimiters
+    ///
+    /// Differs from `token.value` only after the value has been rewritten,
+    /// e.g. by [`normalize_line_endings`].
+    pub fn raw_value<'a>(&self, source: &'a str) -> &'a str {
+        let raw = self.span.slice(source);
+        match self.token.token_type {
+            TokenType::AttributeStringValue | TokenType::AttributeExpressionValue => {
+                &raw[1..raw.len() - 1]
+            }
+            _ => raw,
+        }
+    }
+}
+
+/// Replace each `\r\n` in the text tokens' values with `\n`
+///
+/// Only text can contain line breaks. Spans are left alone, so the original
+/// text stays available through [`SpannedToken::raw_value`].
+///
+/// ```rust
+/// use jsx_compilation_rs::{normalize_line_endings, tokenize_spanned};
+///
+/// let source = "<p>one\r\ntwo</p>";
+/// let mut tokens = tokenize_spanned(source).unwrap();
+/// normalize_line_endings(&mut tokens);
+/// assert_eq!(tokens[3].token.value, "one\ntwo");
+/// assert_eq!(tokens[3].raw_value(source), "one\r\ntwo");
+/// ```
+pub fn normalize_line_endings(tokens: &mut [SpannedToken]) {
+    for token in tokens {
+        let token = &mut token.token;
+        if token.token_type == TokenType::JSXText && token.value.contains("\r\n") {
+            token.value = token.value.replace("\r\n", "\n");
+        }
+    }
+}
@@ -1,6 +1,63 @@
 #[cfg(test)]
 mod tests {
     use crate::tokenizer::{tokenizer, Token, TokenType};
+    use crate::arithmetic::{self, ASTNode, ArithmeticError, NodeType};
+    use crate::ast::{
+        Expression, JSXAttributeItem, JSXAttributeValue, JSXChild, JSXElement, LiteralValue,
+        ObjectMember,
+    };
+    use crate::batch::{tokenize_batch, BatchError, BatchInput, BatchOptions, BatchSource};
+    use crate::budget::{tokenize_budgeted, Budget, Paused, Progress};
+    use crate::dfa::{check, CharClass, Op, Rule, SpecIssue, SPEC};
+    use crate::diagnostic::{apply_suggestions, fix, suggest_fixes, FixKind, Suggestion};
+    use crate::encoding::{decode, tokenize_bytes, Encoding};
+    use crate::error_to_json;
+    use crate::expression::{parse_expression, ExpressionError};
+    use crate::frame::{render_code_frame, FrameOptions};
+    use crate::incremental::{retokenize, TextEdit};
+    use crate::intern::{tokenize_interned, InternedToken, InternedValue, Interner};
+    use crate::iter::tokenize_iter;
+    use crate::locale::Locale;
+    use crate::nesting::{check_nesting, NestingError};
+    use crate::parser::{parse, ParseError};
+    use crate::push::PushTokenizer;
+    use crate::reader::{tokenize_reader, ReadError};
+    use crate::roles::{tokenize_roles, TokenRole};
+    use crate::sax::{tokenize_events, AttributeValue, JsxHandler};
+    use crate::span::{normalize_line_endings, Position, Span, SpannedToken};
+    use crate::token_reader::{TokenReader, UnexpectedToken};
+    use crate::tokenizer::{
+        tokenize_spanned, Checkpoint, ErrorReport, State, Tokenizer, TokenizerError,
+    };
+    use crate::validate::validate;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::ops::ControlFlow;
+
+    /// Counts allocations made by the current thread, so tests can assert a path allocates nothing
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
 
     #[test]
     fn test_full_call_expression_with_string_attributes() {
@@ -253,4 +310,1598 @@ mod tests {
         assert_eq!(result1.len(), 8);
         assert_eq!(result2.len(), 8);
     }
+
+    #[test]
+    fn test_error_messages_default_to_english() {
+        let err = tokenizer("invalid").unwrap_err();
+        assert_eq!(err.to_string(), "First character must be <");
+
+        let err = tokenizer("<div@>").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected character: @");
+    }
+
+    #[test]
+    fn test_error_messages_localized() {
+        let err = tokenizer("invalid").unwrap_err();
+        assert_eq!(err.message(Locale::ZhCn), "第一个字符必须是<");
+        assert_eq!(err.localized(Locale::En).to_string(), "First character must be <");
+
+        let err = tokenizer("<div@>").unwrap_err();
+        assert_eq!(err.localized(Locale::ZhCn).to_string(), "意外的字符: @");
+    }
+
+    #[test]
+    fn test_locale_parsing() {
+        assert_eq!("en".parse::<Locale>(), Ok(Locale::En));
+        assert_eq!("zh-CN".parse::<Locale>(), Ok(Locale::ZhCn));
+        assert_eq!("zh_cn".parse::<Locale>(), Ok(Locale::ZhCn));
+        assert!("fr".parse::<Locale>().is_err());
+
+        for locale in Locale::ALL {
+            assert_eq!(locale.tag().parse::<Locale>(), Ok(locale));
+        }
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(TokenizerError::InvalidFirstCharacter.code(), "JSX0001");
+        assert_eq!(TokenizerError::UnexpectedCharacter('@').code(), "JSX0002");
+        assert_eq!(TokenizerError::UnexpectedEndOfInput.code(), "JSX0003");
+    }
+
+    #[test]
+    fn test_error_json_round_trip() {
+        let err = tokenizer("<div@>").unwrap_err();
+        let json = error_to_json(&err, Locale::ZhCn).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["code"], "JSX0002");
+        assert_eq!(value["kind"], "UnexpectedCharacter");
+        assert_eq!(value["character"], "@");
+        assert_eq!(value["message"], "意外的字符: @");
+
+        let report: ErrorReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.error, err);
+
+        let unit = serde_json::to_string(&TokenizerError::InvalidFirstCharacter).unwrap();
+        assert_eq!(unit, r#"{"kind":"InvalidFirstCharacter"}"#);
+        assert_eq!(
+            serde_json::from_str::<TokenizerError>(&unit).unwrap(),
+            TokenizerError::InvalidFirstCharacter
+        );
+    }
+
+    #[test]
+    fn test_spanned_tokens() {
+        let source_code = r#"<h1 id="title" name={name}>hi</h1>"#;
+        let tokens = tokenize_spanned(source_code).unwrap();
+
+        let plain: Vec<Token> = tokens.iter().map(|t| t.token.clone()).collect();
+        assert_eq!(plain, tokenizer(source_code).unwrap());
+
+        let slices: Vec<&str> = tokens.iter().map(|t| t.span.slice(source_code)).collect();
+        assert_eq!(
+            slices,
+            vec!["<", "h1", "id", "\"title\"", "name", "{name}", ">", "hi", "<", "/", "h1", ">"]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_locates_error() {
+        let diagnostic = tokenize_spanned("<div@>").unwrap_err();
+        assert_eq!(diagnostic.error, TokenizerError::UnexpectedCharacter('@'));
+        assert_eq!(diagnostic.span, Span::new(4, 5));
+        assert_eq!(diagnostic.code(), "JSX0002");
+    }
+
+    #[test]
+    fn test_suggest_quoting_attribute_value() {
+        let source_code = "<h1 id=title>hi</h1>";
+        let diagnostic = tokenize_spanned(source_code).unwrap_err();
+        assert_eq!(
+            diagnostic.suggestions,
+            vec![Suggestion::new(FixKind::QuoteAttributeValue, Span::new(7, 12), "\"title\"")]
+        );
+        assert_eq!(
+            apply_suggestions(source_code, &diagnostic.suggestions),
+            r#"<h1 id="title">hi</h1>"#
+        );
+
+        let diagnostic = tokenize_spanned("<h1 id='title'>hi</h1>").unwrap_err();
+        assert_eq!(diagnostic.suggestions[0].kind, FixKind::UseDoubleQuotes);
+        assert_eq!(diagnostic.suggestions[0].span, Span::new(7, 14));
+    }
+
+    #[test]
+    fn test_suggest_expanding_self_closing_tag() {
+        assert_eq!(fix("<img/>"), "<img></img>");
+        assert_eq!(fix("<img />"), "<img></img>");
+        assert_eq!(fix(r#"<img src="a"/>"#), r#"<img src="a"></img>"#);
+    }
+
+    #[test]
+    fn test_lint_suggestions() {
+        let suggestions = suggest_fixes(r#"<label class="a" for="b">x</label>"#);
+        let kinds: Vec<&FixKind> = suggestions.iter().map(|s| &s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &FixKind::RenameAttribute { from: "class".to_string(), to: "className".to_string() },
+                &FixKind::RenameAttribute { from: "for".to_string(), to: "htmlFor".to_string() },
+            ]
+        );
+        assert_eq!(suggestions[0].message(Locale::En), "Use className instead of class in JSX");
+
+        assert_eq!(fix("<div><span>hi"), "<div><span>hi</span></div>");
+        assert!(suggest_fixes("<div>hi</div>").is_empty());
+    }
+
+    #[test]
+    fn test_fix_repairs_several_mistakes() {
+        assert_eq!(fix("<div  class=box >"), r#"<div className="box"></div>"#);
+    }
+
+    #[test]
+    fn test_position_of_offset() {
+        let source_code = "<div>\n  <p>é</p>\n</div>";
+        assert_eq!(Position::of(source_code, 0), Position { line: 1, column: 1 });
+        assert_eq!(Position::of(source_code, 8), Position { line: 2, column: 3 });
+        // Columns count characters, not bytes
+        assert_eq!(Position::of(source_code, 13), Position { line: 2, column: 7 });
+    }
+
+    #[test]
+    fn test_render_code_frame() {
+        let source_code = "<div>\n  <h1 id=title>hello</h1>\n</div>";
+        let diagnostic = tokenize_spanned(source_code).unwrap_err();
+        let rendered = diagnostic.render(source_code, Locale::En, FrameOptions::default());
+        assert_eq!(
+            rendered,
+            "error[JSX0002]: Unexpected character: t\n \
+             --> 2:10\n  \
+             |\n\
+             1 | <div>\n\
+             2 |   <h1 id=title>hello</h1>\n  \
+             |          ^ Unexpected character: t\n\
+             3 | </div>\n  \
+             = help: Wrap the attribute value in double quotes\n"
+        );
+
+        let colored = diagnostic.render(source_code, Locale::ZhCn, FrameOptions::colored());
+        assert!(colored.contains("\x1b[1;31m^\x1b[0m"));
+        assert!(colored.contains("意外的字符: t"));
+    }
+
+    #[test]
+    fn test_render_code_frame_context_and_span_width() {
+        let source_code = "a\nb\nc\nd\n<span>\ne\nf\ng";
+        let options = FrameOptions { context_lines: 1, color: false };
+        let rendered = render_code_frame(source_code, Span::new(8, 14), "here", options);
+        assert_eq!(rendered, "  |\n4 | d\n5 | <span>\n  | ^^^^^^ here\n6 | e\n");
+    }
+
+    #[test]
+    fn test_role_tokens() {
+        let source_code = r#"<div id="a"><img/>hi</div>"#;
+        let tokens = tokenize_roles(source_code).unwrap();
+        let roles: Vec<(TokenRole, &str)> = tokens.iter().map(|t| (t.role, t.value.as_str())).collect();
+        assert_eq!(
+            roles,
+            vec![
+                (TokenRole::OpeningTagStart, "<"),
+                (TokenRole::TagName, "div"),
+                (TokenRole::AttributeName, "id"),
+                (TokenRole::AttributeStringValue, "a"),
+                (TokenRole::TagEnd, ">"),
+                (TokenRole::OpeningTagStart, "<"),
+                (TokenRole::TagName, "img"),
+                (TokenRole::SelfClosingTagEnd, "/>"),
+                (TokenRole::Text, "hi"),
+                (TokenRole::ClosingTagStart, "</"),
+                (TokenRole::TagName, "div"),
+                (TokenRole::TagEnd, ">"),
+            ]
+        );
+        assert_eq!(tokens[7].span.slice(source_code), "/>");
+        assert_eq!(tokens[9].span.slice(source_code), "</");
+    }
+
+    #[test]
+    fn test_role_tokens_self_closing_with_attributes() {
+        let tokens = tokenize_roles(r#"<img src="a" />"#).unwrap();
+        assert_eq!(tokens.last().unwrap().role, TokenRole::SelfClosingTagEnd);
+        assert_eq!(tokens.len(), 5);
+
+        // The compatible token stream still rejects self-closing tags
+        assert!(tokenizer("<img/>").is_err());
+        assert!(tokenize_roles("<img/ >").is_err());
+    }
+
+    #[test]
+    fn test_role_tokens_reject_self_closing_closing_tag() {
+        let err = tokenize_roles("</a/>").unwrap_err();
+        assert_eq!(err.error, TokenizerError::UnexpectedCharacter('/'));
+        assert_eq!(err.span, Span::new(3, 4));
+        assert!(tokenize_roles("<a></b/><c>").is_err());
+        assert!(tokenize_roles("<a><b/></a><c/>").is_ok());
+    }
+
+    #[test]
+    fn test_token_iterator_matches_tokenizer() {
+        let source_code = r#"<h1 id="title" name={name}><span>hello</span>world</h1>"#;
+        let collected: Result<Vec<Token>, TokenizerError> = tokenize_iter(source_code).collect();
+        assert_eq!(collected.unwrap(), tokenizer(source_code).unwrap());
+    }
+
+    #[test]
+    fn test_token_iterator_is_lazy_and_stops_at_error() {
+        // Tokens before the error are still produced
+        let mut tokens = tokenize_iter("<div>text<@");
+        assert_eq!(tokens.next(), Some(Ok(Token::new(TokenType::LeftParentheses, "<".to_string()))));
+        let rest: Vec<_> = tokens.by_ref().collect();
+        assert_eq!(rest.last(), Some(&Err(TokenizerError::UnexpectedCharacter('@'))));
+        assert_eq!(rest.len(), 5);
+        assert_eq!(tokens.next(), None);
+
+        // Taking a prefix never reaches the invalid tail
+        let first_two: Vec<_> = tokenize_iter("<a>@@@<").take(2).collect();
+        assert!(first_two.iter().all(|token| token.is_ok()));
+    }
+
+    /// Feed `source_code` to a push tokenizer in chunks of `size` characters
+    fn tokenize_in_chunks(source_code: &str, size: usize) -> Result<Vec<Token>, TokenizerError> {
+        let chars: Vec<char> = source_code.chars().collect();
+        let mut push = PushTokenizer::new();
+        let mut tokens = Vec::new();
+        for chunk in chars.chunks(size) {
+            tokens.extend(push.feed(&chunk.iter().collect::<String>())?);
+        }
+        tokens.extend(push.finish()?);
+        Ok(tokens)
+    }
+
+    #[test]
+    fn test_push_tokenizer_any_chunk_size() {
+        let source_code = r#"<h1 id="title" name={name}><span>héllo</span>world</h1>"#;
+        let expected = tokenizer(source_code).unwrap();
+        for size in 1..=source_code.len() {
+            assert_eq!(tokenize_in_chunks(source_code, size).unwrap(), expected, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_push_tokenizer_finish_validates_end_of_input() {
+        assert_eq!(tokenize_in_chunks("<div id=\"ti", 3), Err(TokenizerError::UnexpectedEndOfInput));
+        assert_eq!(tokenize_in_chunks("", 3), Ok(vec![]));
+
+        // Trailing text is emitted rather than dropped
+        let tokens = tokenize_in_chunks("<br>after", 2).unwrap();
+        assert_eq!(tokens.last(), Some(&Token::new(TokenType::JSXText, "after".to_string())));
+    }
+
+    #[test]
+    fn test_push_tokenizer_error_is_sticky() {
+        let mut push = PushTokenizer::new();
+        assert!(push.feed("<di").is_ok());
+        assert_eq!(push.feed("v@>"), Err(TokenizerError::UnexpectedCharacter('@')));
+        assert_eq!(push.offset(), 4);
+        assert_eq!(push.feed("</div>"), Err(TokenizerError::UnexpectedCharacter('@')));
+        assert!(push.finish().is_err());
+    }
+
+    /// Reader that hands out at most `step` bytes per read, to split characters across reads
+    struct TrickleReader<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl std::io::Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..n].copy_from_slice(&self.bytes[..n]);
+            self.bytes = &self.bytes[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_reader_handles_split_utf8() {
+        let source_code = r#"<p title="x">héllo 你好 🎉</p>"#;
+        let expected = tokenizer(source_code).unwrap();
+        for step in 1..=5 {
+            let reader = TrickleReader { bytes: source_code.as_bytes(), step };
+            let tokens: Vec<Token> = tokenize_reader(reader).collect::<Result<_, _>>().unwrap();
+            assert_eq!(tokens, expected, "step {}", step);
+        }
+    }
+
+    #[test]
+    fn test_reader_reports_invalid_utf8_and_tokenizer_errors() {
+        let bytes = b"<p>ok\xFFno</p>";
+        let results: Vec<_> = tokenize_reader(&bytes[..]).collect();
+        assert!(results[..3].iter().all(|r| r.is_ok()));
+        assert!(matches!(results.last(), Some(Err(ReadError::InvalidUtf8 { offset: 5 }))));
+
+        // Truncated multi-byte sequence at the end of input
+        let results: Vec<_> = tokenize_reader(&"<p>你".as_bytes()[..4]).collect();
+        assert!(matches!(results.last(), Some(Err(ReadError::InvalidUtf8 { offset: 3 }))));
+
+        let results: Vec<_> = tokenize_reader("<p@>".as_bytes()).collect();
+        assert!(matches!(
+            results.last(),
+            Some(Err(ReadError::Tokenizer { error: TokenizerError::UnexpectedCharacter('@'), offset: 2 }))
+        ));
+    }
+
+    #[test]
+    fn test_reader_delivers_tokens_before_error() {
+        let results: Vec<_> = tokenize_reader("<p>hi</p@>".as_bytes()).collect();
+        assert_eq!(results.len(), 7);
+        assert!(results[..6].iter().all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn test_retokenize_matches_full_tokenization() {
+        let old_source = r#"<div id="main"><h1 title="x">Hello</h1><p>one</p><p>two</p></div>"#;
+        let old_tokens = tokenize_spanned(old_source).unwrap();
+        let edits = [
+            TextEdit::new(Span::new(29, 34), "Goodbye"),
+            TextEdit::new(Span::new(9, 13), "app"),
+            TextEdit::new(Span::new(0, 0), "<section>"),
+            TextEdit::new(Span::new(42, 45), ""),
+            TextEdit::new(Span::new(39, 49), "<span>new</span>"),
+            TextEdit::new(Span::new(old_source.len(), old_source.len()), "tail"),
+            TextEdit::new(Span::new(15, 18), "<h2"),
+        ];
+        for edit in &edits {
+            let new_source = edit.apply(old_source);
+            let result = retokenize(&new_source, &old_tokens, edit).unwrap();
+            assert_eq!(result.tokens, tokenize_spanned(&new_source).unwrap(), "{:?}", edit);
+        }
+    }
+
+    #[test]
+    fn test_retokenize_reuses_tokens_after_edit() {
+        let old_source = "<ul><li>one</li><li>two</li><li>three</li></ul>";
+        let old_tokens = tokenize_spanned(old_source).unwrap();
+
+        let edit = TextEdit::new(Span::new(20, 23), "deux");
+        let new_source = edit.apply(old_source);
+        let result = retokenize(&new_source, &old_tokens, &edit).unwrap();
+        assert_eq!(result.changed, 14..15);
+        assert_eq!(result.replaced, 14..15);
+        assert_eq!(result.tokens[14].token.value, "deux");
+        assert_eq!(result.tokens.len(), old_tokens.len());
+    }
+
+    #[test]
+    fn test_retokenize_reports_errors_in_new_source() {
+        let old_source = "<div>text</div>";
+        let old_tokens = tokenize_spanned(old_source).unwrap();
+        let edit = TextEdit::new(Span::new(11, 11), "@");
+        let new_source = edit.apply(old_source);
+        let diagnostic = retokenize(&new_source, &old_tokens, &edit).unwrap_err();
+        assert_eq!(diagnostic.span, Span::new(11, 12));
+        assert_eq!(diagnostic, tokenize_spanned(&new_source).unwrap_err());
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_at_every_offset() {
+        let source_code = r#"<div id="a"><p class={x}>hi</p><b>yo</b></div>"#;
+        let expected = PushTokenizer::new().feed(source_code).unwrap();
+
+        for split in 1..source_code.len() {
+            let mut push = PushTokenizer::new();
+            let mut tokens = push.feed(&source_code[..split]).unwrap();
+            let json = serde_json::to_string(&push.checkpoint()).unwrap();
+
+            let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+            assert_eq!(checkpoint.offset(), split);
+            let mut push = PushTokenizer::resume(checkpoint);
+            tokens.extend(push.feed(&source_code[split..]).unwrap());
+            assert_eq!(tokens, expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_records_state_and_depth() {
+        let mut push = PushTokenizer::new();
+        push.feed(r#"<div><p class="ab"#).unwrap();
+        let checkpoint = push.checkpoint();
+        assert_eq!(checkpoint.state(), State::AttributeStringValue);
+        assert_eq!(checkpoint.depth(), 1);
+        assert_eq!(checkpoint.partial_token().unwrap().value, "ab");
+
+        push.feed("\">x</p></div").unwrap();
+        let checkpoint = push.checkpoint();
+        assert_eq!(checkpoint.depth(), 1);
+        assert_eq!(checkpoint.partial_token().unwrap().value, "div");
+        push.feed(">").unwrap();
+        assert_eq!(push.checkpoint().depth(), 0);
+    }
+
+    #[test]
+    fn test_bulk_scanning_matches_char_by_char() {
+        let inputs = [
+            r#"<div class="container" id="main">Content</div>"#,
+            r#"<ul><li key={item1}>héllo wörld 你好</li><li>two</li></ul>"#,
+            r#"<p title="abc def">x</p>"#,
+            r#"<p title={a.b}>x</p>"#,
+            "<section>trailing text after the last tag",
+            "<div-test></div-test>",
+            "<p>text</p",
+            "plain text",
+        ];
+        for input in inputs {
+            let char_by_char: Result<Vec<Token>, TokenizerError> = tokenize_iter(input).collect();
+            assert_eq!(tokenizer(input), char_by_char, "input {:?}", input);
+        }
+
+        // Errors found after a bulk run still point at the offending character
+        let source_code = r#"<p>héllo wörld</p><a href="x y">"#;
+        let diagnostic = tokenize_spanned(source_code).unwrap_err();
+        assert_eq!(diagnostic.error, TokenizerError::UnexpectedCharacter(' '));
+        assert_eq!(diagnostic.span, Span::new(30, 31));
+    }
+
+    #[test]
+    fn test_validate_agrees_with_tokenizer() {
+        // Every string of up to five characters over the characters the FSM cares about
+        let alphabet = ['<', '>', '/', '=', '"', '{', '}', ' ', 'a', 'é'];
+        let mut inputs = vec![String::new()];
+        for _ in 0..5 {
+            inputs = inputs
+                .iter()
+                .flat_map(|prefix| alphabet.iter().map(move |ch| format!("{}{}", prefix, ch)))
+                .collect();
+            for input in &inputs {
+                let expected = tokenize_spanned(input)
+                    .map(|_| ())
+                    .map_err(|diagnostic| (diagnostic.error, diagnostic.span.start));
+                assert_eq!(validate(input), expected, "input {:?}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_does_not_allocate() {
+        let source_code = format!(
+            r#"<ul>{}</ul>"#,
+            r#"<li class="item" key={id}>Some text</li>"#.repeat(50)
+        );
+        let before = allocations();
+        assert!(crate::is_valid_jsx(&source_code));
+        assert!(!crate::is_valid_jsx("<ul><li class=item>x</li></ul>"));
+        assert_eq!(allocations(), before);
+    }
+
+    #[test]
+    fn test_transition_spec_check() {
+        assert_eq!(check(SPEC, true), vec![]);
+        assert_eq!(
+            check(SPEC, false),
+            vec![SpecIssue::Unreachable(State::SelfClosingSlash)]
+        );
+
+        // A rule for `>` in text clashes with the catch-all text rule, and a state
+        // nothing leads to is reported
+        let mut spec = SPEC.to_vec();
+        spec.push(Rule {
+            from: &[State::JSXText],
+            on: &[CharClass::Gt],
+            self_closing: false,
+            ops: &[Op::Push],
+            to: State::JSXText,
+        });
+        spec.retain(|rule| rule.to != State::AttributeValue);
+        let issues = check(&spec, true);
+        assert!(issues.contains(&SpecIssue::Ambiguous {
+            state: State::JSXText,
+            class: CharClass::Gt,
+            rules: (spec.len() - 2, spec.len() - 1),
+        }));
+        assert!(issues.contains(&SpecIssue::Unreachable(State::AttributeStringValue)));
+    }
+
+    #[test]
+    fn test_batch_results_keep_input_order() {
+        let inputs: Vec<BatchInput> = (0..200)
+            .map(|i| {
+                let text = if i % 7 == 0 {
+                    format!("<p id={}>broken</p>", i)
+                } else {
+                    format!(r#"<p id="n{}">{}</p>"#, i, "text ".repeat(i))
+                };
+                BatchInput::text(format!("snippet{}.jsx", i), text)
+            })
+            .collect();
+        let expected_tokens: usize = inputs
+            .iter()
+            .filter_map(|input| match &input.source {
+                BatchSource::Text(text) => tokenizer(text).ok(),
+                _ => None,
+            })
+            .map(|tokens| tokens.len())
+            .sum();
+
+        let report = tokenize_batch(inputs, BatchOptions { threads: 4 });
+        for (i, result) in report.results.iter().enumerate() {
+            assert_eq!(result.name, format!("snippet{}.jsx", i));
+            assert_eq!(result.tokens.is_err(), i % 7 == 0);
+        }
+        assert_eq!(report.stats.inputs, 200);
+        assert_eq!(report.stats.failures, 29);
+        assert_eq!(report.failures().count(), 29);
+        assert_eq!(report.stats.tokens, expected_tokens);
+    }
+
+    #[test]
+    fn test_batch_reads_files() {
+        let dir = std::env::temp_dir().join(format!("jsx-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let good = dir.join("good.jsx");
+        std::fs::write(&good, r#"<div class="a">hi</div>"#).unwrap();
+
+        let report = tokenize_batch(
+            vec![
+                BatchInput::file(&good),
+                BatchInput::file(dir.join("missing.jsx")),
+                BatchInput::text("inline", "<b>x</b>"),
+            ],
+            BatchOptions { threads: 2 },
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.results[0].name, good.display().to_string());
+        assert_eq!(report.results[0].tokens.as_ref().unwrap().len(), 10);
+        assert!(matches!(report.results[1].tokens, Err(BatchError::Io(_))));
+        assert_eq!(report.results[2].tokens.as_ref().unwrap().len(), 8);
+        assert_eq!(report.stats.tokens, 18);
+        assert_eq!(report.stats.failures, 1);
+        assert_eq!(report.stats.bytes, 23 + 8);
+    }
+
+    #[test]
+    fn test_interned_tokens_round_trip() {
+        let source_code = r#"<ul className="list"><li className="item">a</li><li className="item">b</li></ul>"#;
+        let mut interner = Interner::new();
+        let tokens = tokenize_interned(source_code, &mut interner).unwrap();
+
+        // ul, className, li
+        assert_eq!(interner.len(), 3);
+        let li = interner.get("li").unwrap();
+        assert_eq!(tokens.iter().filter(|t| t.symbol() == Some(li)).count(), 4);
+        assert_eq!(tokens[3].value, InternedValue::Text("list".to_string()));
+
+        let json = serde_json::to_string(&(&interner, &tokens)).unwrap();
+        assert!(json.starts_with(r#"[["ul","className","li"],[{"type":"LeftParentheses","value":{"text":"<"}},{"type":"JSXIdentifier","value":{"symbol":0}}"#));
+        let (interner, tokens): (Interner, Vec<InternedToken>) = serde_json::from_str(&json).unwrap();
+        let resolved: Vec<Token> = tokens.iter().map(|t| t.resolve(&interner).unwrap()).collect();
+        assert_eq!(resolved, tokenizer(source_code).unwrap());
+
+        // A symbol from a larger interner does not resolve in a smaller one
+        assert_eq!(Interner::new().resolve(li), None);
+        assert!(serde_json::from_str::<Interner>(r#"["a","a"]"#).is_err());
+    }
+
+    #[test]
+    fn test_interned_tokens_skip_name_allocations() {
+        let source_code = r#"<ul className="list"><li className="item">a</li><li className="item">b</li></ul>"#;
+        let mut interner = Interner::new();
+        tokenize_interned(source_code, &mut interner).unwrap();
+
+        let before = allocations();
+        let plain = tokenizer(source_code).unwrap();
+        let plain_allocations = allocations() - before;
+
+        // With the names interned, none of them is copied out of the input again
+        let before = allocations();
+        let tokens = tokenize_interned(source_code, &mut interner).unwrap();
+        let interned_allocations = allocations() - before;
+        let names = tokens.iter().filter(|t| t.symbol().is_some()).count();
+        assert_eq!(names, 9);
+        assert!(
+            interned_allocations + names <= plain_allocations,
+            "{} allocations interned, {} plain",
+            interned_allocations,
+            plain_allocations
+        );
+        assert_eq!(interner.len(), 3);
+        let resolved: Vec<Token> = tokens.iter().map(|t| t.resolve(&interner).unwrap()).collect();
+        assert_eq!(resolved, plain);
+
+        // Attributes without a key and failures match the plain tokenizer too
+        let resolved: Vec<Token> = tokenize_interned(r#"<a ="x"></a>"#, &mut interner)
+            .unwrap()
+            .iter()
+            .map(|t| t.resolve(&interner).unwrap())
+            .collect();
+        assert_eq!(resolved, tokenizer(r#"<a ="x"></a>"#).unwrap());
+        assert_eq!(
+            tokenize_interned("<a b=c>", &mut interner).unwrap_err(),
+            tokenizer("<a b=c>").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_tokenize_into_reuses_buffers() {
+        let sources = [
+            r#"<ul className="list"><li key={a}>one</li></ul>"#,
+            "<p>two</p>",
+            r#"<h1 id="title"><span>three</span></h1>"#,
+        ];
+        let mut lexer = Tokenizer::new();
+        let mut tokens = Vec::new();
+        for source in sources {
+            tokens.clear();
+            lexer.tokenize_into(source, &mut tokens).unwrap();
+            assert_eq!(tokens, tokenizer(source).unwrap());
+        }
+
+        // Once warmed up, the token buffer is not reallocated; only the token
+        // values themselves are
+        let source = sources[0];
+        let before = allocations();
+        let fresh = tokenizer(source).unwrap();
+        let fresh_allocations = allocations() - before;
+        tokens.clear();
+        let buffer = tokens.as_ptr();
+        let before = allocations();
+        lexer.tokenize_into(source, &mut tokens).unwrap();
+        assert!(allocations() - before < fresh_allocations);
+        assert_eq!(tokens.as_ptr(), buffer);
+        assert_eq!(tokens, fresh);
+        let len = tokens.len();
+
+        // Appends, and leaves the output untouched on failure
+        lexer.tokenize_into("<p>two</p>", &mut tokens).unwrap();
+        assert_eq!(tokens.len(), len + 8);
+        assert!(lexer.tokenize_into("<p>text<@", &mut tokens).is_err());
+        assert_eq!(tokens.len(), len + 8);
+
+        // A failed input does not leak state into the next one
+        tokens.clear();
+        lexer.tokenize_into(sources[1], &mut tokens).unwrap();
+        assert_eq!(tokens, tokenizer(sources[1]).unwrap());
+    }
+
+    /// Records every event as a string, stopping after `limit` events
+    struct EventLog {
+        events: Vec<String>,
+        limit: usize,
+    }
+
+    impl EventLog {
+        fn record(&mut self, event: String) -> ControlFlow<()> {
+            self.events.push(event);
+            if self.events.len() == self.limit {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    impl JsxHandler for EventLog {
+        fn open_element(&mut self, name: &str, span: Span) -> ControlFlow<()> {
+            self.record(format!("open {} {:?}", name, span))
+        }
+
+        fn attribute(&mut self, key: &str, value: AttributeValue<'_>, span: Span) -> ControlFlow<()> {
+            self.record(format!("attribute {} {:?} {:?}", key, value, span))
+        }
+
+        fn close_element(&mut self, name: &str, span: Span) -> ControlFlow<()> {
+            self.record(format!("close {} {:?}", name, span))
+        }
+
+        fn text(&mut self, text: &str, span: Span) -> ControlFlow<()> {
+            self.record(format!("text {} {:?}", text, span))
+        }
+
+        fn expression(&mut self, code: &str, span: Span) -> ControlFlow<()> {
+            self.record(format!("expression {} {:?}", code, span))
+        }
+    }
+
+    #[test]
+    fn test_events_report_constructs_in_order() {
+        let source_code = r#"<h1 id="title" name={name}>héllo<br></br></h1>"#;
+        let mut log = EventLog {
+            events: Vec::new(),
+            limit: usize::MAX,
+        };
+        let flow = tokenize_events(source_code, &mut log).unwrap();
+        assert_eq!(flow, ControlFlow::Continue(()));
+        assert_eq!(
+            log.events,
+            vec![
+                "open h1 Span { start: 1, end: 3 }",
+                r#"attribute id String("title") Span { start: 4, end: 14 }"#,
+                "expression name Span { start: 20, end: 26 }",
+                r#"attribute name Expression("name") Span { start: 15, end: 26 }"#,
+                "text héllo Span { start: 27, end: 33 }",
+                "open br Span { start: 34, end: 36 }",
+                "close br Span { start: 39, end: 41 }",
+                "close h1 Span { start: 44, end: 46 }",
+            ]
+        );
+
+        // Stopping early leaves the rest of the input unread, errors included
+        for limit in 1..log.events.len() {
+            let mut partial = EventLog {
+                events: Vec::new(),
+                limit,
+            };
+            let flow = tokenize_events(&format!("{}<@", source_code), &mut partial).unwrap();
+            assert!(flow.is_break());
+            assert_eq!(partial.events[..], log.events[..limit]);
+        }
+
+        // Errors carry the offset, as with validate
+        let mut log = EventLog {
+            events: Vec::new(),
+            limit: usize::MAX,
+        };
+        assert_eq!(
+            tokenize_events("<p class=x>", &mut log),
+            Err((TokenizerError::UnexpectedCharacter('x'), 9))
+        );
+        assert_eq!(log.events, vec!["open p Span { start: 1, end: 2 }"]);
+    }
+
+    #[test]
+    fn test_events_do_not_allocate() {
+        struct Count(usize);
+        impl JsxHandler for Count {
+            fn open_element(&mut self, _: &str, _: Span) -> ControlFlow<()> {
+                self.0 += 1;
+                ControlFlow::Continue(())
+            }
+        }
+
+        let source_code = r#"<ul className="list"><li key={a}>one</li><li>two</li></ul>"#;
+        let mut count = Count(0);
+        let before = allocations();
+        assert!(tokenize_events(source_code, &mut count).is_ok());
+        assert_eq!(allocations(), before);
+        assert_eq!(count.0, 3);
+    }
+
+    #[test]
+    fn test_budgeted_tokenization_resumes_to_same_tokens() {
+        let source_code = format!(
+            r#"<ul>{}</ul>"#,
+            r#"<li class="item" key={id}>Some text é</li>"#.repeat(150)
+        );
+        let expected = tokenizer(&source_code).unwrap();
+        for steps in [1, 7, 1000, 5000, usize::MAX] {
+            let mut progress = tokenize_budgeted(&source_code, Budget::steps(steps)).unwrap();
+            let mut offset = 0;
+            let tokens = loop {
+                match progress {
+                    Progress::Done(tokens) => break tokens,
+                    Progress::Paused(paused) => {
+                        // Every run makes progress and pauses on a character boundary
+                        assert!(paused.offset() > offset);
+                        assert!(source_code.is_char_boundary(paused.offset()));
+                        // Paused runs survive a round trip through JSON
+                        let paused = if offset == 0 {
+                            let json = serde_json::to_string(&paused).unwrap();
+                            serde_json::from_str::<Paused>(&json).unwrap()
+                        } else {
+                            paused
+                        };
+                        offset = paused.offset();
+                        progress = paused.resume(&source_code, Budget::steps(steps)).unwrap();
+                    }
+                }
+            };
+            assert_eq!(tokens, expected, "steps {}", steps);
+        }
+
+        // Errors are found in whichever run reaches them
+        let source_code = format!("{}<p class=x>", source_code);
+        let mut progress = tokenize_budgeted(&source_code, Budget::steps(5000));
+        while let Ok(Progress::Paused(paused)) = progress {
+            progress = paused.resume(&source_code, Budget::steps(5000));
+        }
+        assert_eq!(progress.unwrap_err(), tokenize_spanned(&source_code).unwrap_err());
+    }
+
+    #[test]
+    fn test_budgeted_tokenization_can_be_cancelled() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let source_code = format!("<p>{}</p>", "text ".repeat(10_000));
+        let cancel = AtomicBool::new(true);
+        let progress = tokenize_budgeted(&source_code, Budget::cancel(&cancel)).unwrap();
+        let Progress::Paused(paused) = progress else {
+            panic!("a cancelled run should pause");
+        };
+        assert_eq!(paused.offset(), 0);
+        assert!(paused.tokens().is_empty());
+
+        cancel.store(false, Ordering::Relaxed);
+        let progress = paused.resume(&source_code, Budget::cancel(&cancel)).unwrap();
+        assert_eq!(progress, Progress::Done(tokenizer(&source_code).unwrap()));
+    }
+
+    #[test]
+    fn test_tokenize_bytes_decodes_each_encoding() {
+        let source_code = r#"<p title="x">café 你好 🎉</p>"#;
+        let expected = tokenize_spanned(source_code).unwrap();
+        let utf16 = |to_bytes: fn(u16) -> [u8; 2]| -> Vec<u8> {
+            source_code.encode_utf16().flat_map(to_bytes).collect()
+        };
+
+        let inputs = [
+            (source_code.as_bytes().to_vec(), None, Encoding::Utf8, 0),
+            (
+                [&[0xEF, 0xBB, 0xBF], source_code.as_bytes()].concat(),
+                None,
+                Encoding::Utf8,
+                3,
+            ),
+            (utf16(u16::to_le_bytes), None, Encoding::Utf16Le, 0),
+            (utf16(u16::to_be_bytes), None, Encoding::Utf16Be, 0),
+            (
+                [&[0xFE, 0xFF][..], &utf16(u16::to_be_bytes)].concat(),
+                Some(Encoding::Utf8),
+                Encoding::Utf16Be,
+                2,
+            ),
+        ];
+        for (bytes, declared, encoding, bom) in inputs {
+            let result = tokenize_bytes(&bytes, declared);
+            assert_eq!(result.source.encoding, encoding);
+            assert_eq!(result.source.bom, bom);
+            assert!(result.source.invalid.is_empty());
+            assert_eq!(result.tokens.unwrap(), expected);
+
+            // Every character boundary maps back to the start of its encoding
+            let decoded = &result.source;
+            let unit = match encoding {
+                Encoding::Utf8 => 1,
+                _ => 2,
+            };
+            let mut input = bom;
+            for (offset, ch) in source_code.char_indices() {
+                assert_eq!(decoded.input_offset(offset), input, "{:?} at {}", encoding, offset);
+                input += match encoding {
+                    Encoding::Utf8 => ch.len_utf8(),
+                    _ => unit * ch.len_utf16(),
+                };
+            }
+            assert_eq!(decoded.input_offset(source_code.len()), bytes.len());
+        }
+
+        let latin1 = decode(b"<p title=\"\xE9\">na\xEFve</p>", Some(Encoding::Latin1));
+        assert_eq!(latin1.text, "<p title=\"é\">naïve</p>");
+        assert_eq!(latin1.input_offset(latin1.text.len()), 22);
+        assert_eq!("latin1".parse(), Ok(Encoding::Latin1));
+        assert!("ebcdic".parse::<Encoding>().is_err());
+    }
+
+    #[test]
+    fn test_tokenize_bytes_reports_invalid_sequences() {
+        // A stray Latin-1 byte, a truncated sequence and an invalid lead byte
+        let bytes = b"<p>caf\xE9</p><b>\xE4\xBD</b><i>\xFFx</i>";
+        let result = tokenize_bytes(bytes, None);
+        let source = &result.source;
+        let spans: Vec<Span> = source.invalid.iter().map(|invalid| invalid.span).collect();
+        assert_eq!(spans, vec![Span::new(6, 7), Span::new(14, 16), Span::new(23, 24)]);
+        assert_eq!(
+            source.invalid[0].message(Locale::En),
+            "Invalid utf-8 byte sequence at byte 6"
+        );
+        assert_eq!(source.invalid[0].code(), "JSX0004");
+
+        // Lexing carries on, and spans map back past the replacements
+        let tokens = result.tokens.unwrap();
+        let texts: Vec<&SpannedToken> = tokens
+            .iter()
+            .filter(|t| t.token.token_type == TokenType::JSXText)
+            .collect();
+        assert_eq!(texts[0].token.value, "caf\u{FFFD}");
+        assert_eq!(source.input_span(texts[0].span), Span::new(3, 7));
+        assert_eq!(source.input_span(texts[1].span), Span::new(14, 16));
+        assert_eq!(source.input_span(texts[2].span), Span::new(23, 25));
+
+        // UTF-16 with an unpaired surrogate and an odd trailing byte
+        let mut bytes: Vec<u8> = "<p>".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        bytes.extend([0x00, 0xD8, b'<', 0x00, 0x41]);
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.text, "<p>\u{FFFD}<\u{FFFD}");
+        let spans: Vec<Span> = decoded.invalid.iter().map(|invalid| invalid.span).collect();
+        assert_eq!(spans, vec![Span::new(6, 8), Span::new(10, 11)]);
+
+        // Errors are still reported by the tokenizer
+        let result = tokenize_bytes(b"<p class=\xE9>", None);
+        assert!(result.tokens.is_err());
+        assert_eq!(result.source.invalid.len(), 1);
+    }
+
+    #[test]
+    fn test_crlf_is_one_line_terminator() {
+        let source_code = "<div>\r\n  <p id=x>\r\n</div>";
+        // The `\r` and `\n` of a line break share a position at the end of the line
+        assert_eq!(Position::of(source_code, 5), Position { line: 1, column: 6 });
+        assert_eq!(Position::of(source_code, 6), Position { line: 1, column: 6 });
+        assert_eq!(Position::of(source_code, 7), Position { line: 2, column: 1 });
+        assert_eq!(Position::of(source_code, 15), Position { line: 2, column: 9 });
+        // A lone `\r` is an ordinary character
+        assert_eq!(Position::of("<p>\rx</p>", 5), Position { line: 1, column: 6 });
+
+        let diagnostic = tokenize_spanned(source_code).unwrap_err();
+        assert_eq!(Position::of(source_code, diagnostic.span.start).to_string(), "2:9");
+        let rendered = render_code_frame(source_code, diagnostic.span, "", FrameOptions::default());
+        assert!(rendered.contains("2 |   <p id=x>\n  |         ^\n"));
+    }
+
+    #[test]
+    fn test_normalize_line_endings_keeps_raw_values() {
+        let source_code = "<ul>\r\n<li key={id} title=\"a\">one\r\r\ntwo\r\n</li></ul>";
+        let raw = tokenize_spanned(source_code).unwrap();
+        let mut tokens = raw.clone();
+        normalize_line_endings(&mut tokens);
+
+        let values: Vec<&str> = tokens.iter().map(|t| t.token.value.as_str()).collect();
+        assert_eq!(values[3], "\n");
+        assert_eq!(values[11], "one\r\ntwo\n");
+        for (token, raw) in tokens.iter().zip(&raw) {
+            assert_eq!(token.span, raw.span);
+            assert_eq!(token.raw_value(source_code), raw.token.value);
+        }
+    }
+
+    #[test]
+    fn test_parse_builds_tree_with_spans() {
+        let source_code = r#"<div id="app" onClick={go}><p>Hi</p> there</div>"#;
+        let root = parse(source_code).unwrap();
+        assert_eq!(root.name, "div");
+        assert_eq!(root.span, Span::new(0, source_code.len()));
+        assert_eq!(root.opening_span, Span::new(0, 27));
+        assert_eq!(root.closing_span, Some(Span::new(42, 48)));
+
+        let JSXAttributeItem::JSXAttribute(id) = &root.attributes[0] else {
+            panic!("expected an attribute")
+        };
+        assert_eq!(id.name, "id");
+        assert_eq!(id.span, Span::new(5, 13));
+        assert!(matches!(&id.value, Some(JSXAttributeValue::StringLiteral(s)) if s.value == "app"));
+        let JSXAttributeItem::JSXAttribute(on_click) = &root.attributes[1] else {
+            panic!("expected an attribute")
+        };
+        let Some(JSXAttributeValue::JSXExpressionContainer(container)) = &on_click.value else {
+            panic!("expected an expression")
+        };
+        assert!(matches!(
+            &container.expression,
+            Expression::Identifier(identifier) if identifier.name == "go" && identifier.span == Span::new(23, 25)
+        ));
+        assert_eq!(&source_code[container.span.start..container.span.end], "{go}");
+
+        assert_eq!(root.children.len(), 2);
+        let JSXChild::JSXElement(p) = &root.children[0] else {
+            panic!("expected an element")
+        };
+        assert_eq!(&source_code[p.span.start..p.span.end], "<p>Hi</p>");
+        let JSXChild::JSXText(text) = &root.children[1] else {
+            panic!("expected text")
+        };
+        assert_eq!(text.value, " there");
+        assert_eq!(root.children[1].span(), Span::new(36, 42));
+
+        // The tree survives a JSON round trip, with Babel-style node types
+        let json = serde_json::to_value(&root).unwrap();
+        assert_eq!(json["type"], "JSXElement");
+        assert_eq!(json["attributes"][1]["value"]["type"], "JSXExpressionContainer");
+        assert_eq!(json["children"][1]["type"], "JSXText");
+        let back: JSXElement = serde_json::from_value(json).unwrap();
+        assert_eq!(back, root);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse("oops"), Err(ParseError::Syntax { .. })));
+        assert_eq!(
+            parse("<div>text").unwrap_err(),
+            ParseError::Nesting {
+                error: NestingError::UnclosedElement {
+                    name: "div".to_string(),
+                    span: Span::new(0, 5),
+                }
+            }
+        );
+        assert_eq!(
+            parse("<div><p></div>").unwrap_err().to_string(),
+            "Expected </p> but found </div>"
+        );
+        assert_eq!(
+            parse("<a></a><b></b>").unwrap_err(),
+            ParseError::AdjacentElements { span: Span::new(7, 8) }
+        );
+        assert_eq!(
+            parse("<a></a><b></b>").unwrap_err().to_string(),
+            "Adjacent elements must be wrapped in a parent element"
+        );
+    }
+
+    #[test]
+    fn test_nesting_errors() {
+        assert_eq!(check_nesting(r#"<ul><li id="a">one</li><li>two</li></ul>"#).unwrap(), vec![]);
+
+        let source_code = "<div><span></div>";
+        let errors = check_nesting(source_code).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "JSX0005");
+        assert_eq!(errors[0].span(), Span::new(11, 17));
+        assert_eq!(
+            errors[0].message(Locale::ZhCn),
+            "应为 </span>，但找到了 </div>"
+        );
+        // The frame points at the wrong closing tag, then at the opening tag
+        let rendered = errors[0].render(source_code, Locale::En, FrameOptions::default());
+        assert_eq!(
+            rendered,
+            "error[JSX0005]: Expected </span> but found </div>\n --> 1:12\n  |\n1 | <div><span></div>\n  |            ^^^^^^ Expected </span> but found </div>\n --> 1:6\n  |\n1 | <div><span></div>\n  |      ^^^^^^ <span> is opened here\n"
+        );
+
+        // Elements skipped over by a closing tag are unclosed
+        let errors = check_nesting("<a><b><c></a>").unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                NestingError::MismatchedClosingTag {
+                    expected: "c".to_string(),
+                    found: "a".to_string(),
+                    opening: Span::new(6, 9),
+                    closing: Span::new(9, 13),
+                },
+                NestingError::UnclosedElement {
+                    name: "b".to_string(),
+                    span: Span::new(3, 6),
+                },
+            ]
+        );
+
+        // A stray closing tag leaves the open elements as they were
+        let errors = check_nesting("<div></p>\n<p>x</p>").unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                NestingError::StrayClosingTag {
+                    name: "p".to_string(),
+                    span: Span::new(5, 9),
+                },
+                NestingError::UnclosedElement {
+                    name: "div".to_string(),
+                    span: Span::new(0, 5),
+                },
+            ]
+        );
+        assert_eq!(errors[0].to_string(), "Closing tag </p> has no matching opening tag");
+        assert_eq!(errors[1].code(), "JSX0007");
+
+        let json = serde_json::to_value(&errors[0]).unwrap();
+        assert_eq!(json["kind"], "StrayClosingTag");
+        assert!(check_nesting("oops").is_err());
+    }
+
+    #[test]
+    fn test_token_reader() {
+        let tokens = tokenizer(r#"<a href="x">go</a>"#).unwrap();
+        let mut reader = TokenReader::new(&tokens);
+        assert!(reader.peek().is_some_and(|t| t.token_type == TokenType::LeftParentheses));
+        assert_eq!(reader.peek_n(3).unwrap().value, "x");
+        assert_eq!(reader.peek_n(tokens.len()), None);
+
+        // `read` and `unread` behave as in TokenReader.ts
+        reader.unread();
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read().unwrap().token_type, TokenType::LeftParentheses);
+        reader.unread();
+        assert_eq!(reader.position(), 0);
+
+        // A failed `expect` consumes nothing
+        let err = reader.expect(TokenType::JSXIdentifier).unwrap_err();
+        assert_eq!(
+            err,
+            UnexpectedToken {
+                expected: TokenType::JSXIdentifier,
+                found: Some(TokenType::LeftParentheses),
+                index: 0,
+                span: None,
+            }
+        );
+        assert_eq!(reader.position(), 0);
+
+        // Try reading an attribute, then back out
+        reader.expect(TokenType::LeftParentheses).unwrap();
+        reader.expect(TokenType::JSXIdentifier).unwrap();
+        let checkpoint = reader.checkpoint();
+        assert_eq!(reader.expect(TokenType::AttributeKey).unwrap().value, "href");
+        assert!(reader.expect(TokenType::AttributeExpressionValue).is_err());
+        reader.rewind(checkpoint);
+        assert_eq!(reader.remaining().len(), tokens.len() - 2);
+
+        while reader.read().is_some() {}
+        assert!(reader.at_end());
+        let err = reader.expect(TokenType::RightParentheses).unwrap_err();
+        assert_eq!(err.found, None);
+        assert_eq!(err.index, tokens.len());
+        assert_eq!(err.to_string(), "Expected RightParentheses but reached the end of input");
+
+        // Over spanned tokens, errors say where the unexpected token is
+        let spanned = tokenize_spanned("<a>go</a>").unwrap();
+        let mut reader = TokenReader::new(&spanned);
+        reader.read();
+        let err = reader.expect(TokenType::RightParentheses).unwrap_err();
+        assert_eq!(err.span, Some(Span::new(1, 2)));
+    }
+
+    #[test]
+    fn test_arithmetic_matches_typescript_json() {
+        // The tree from tests/parser.spec.ts and the value from tests/evaluate.spec.ts
+        let ast = arithmetic::parse("2+3*4").unwrap();
+        let expected = serde_json::json!({
+            "type": "Program",
+            "children": [{
+                "type": "Additive",
+                "children": [
+                    {"type": "Numeric", "value": "2"},
+                    {
+                        "type": "Multiplicative",
+                        "children": [
+                            {"type": "Numeric", "value": "3"},
+                            {"type": "Numeric", "value": "4"}
+                        ]
+                    }
+                ]
+            }]
+        });
+        assert_eq!(serde_json::to_value(&ast).unwrap(), expected);
+        let from_json: ASTNode = serde_json::from_value(expected).unwrap();
+        assert_eq!(from_json, ast);
+        assert_eq!(arithmetic::evaluate(&from_json), 14.0);
+
+        // `+` and `*` chains nest to the right, as toAST.ts builds them
+        let ast = arithmetic::parse("1+2+3").unwrap();
+        assert_eq!(
+            serde_json::to_value(&ast).unwrap(),
+            serde_json::json!({
+                "type": "Program",
+                "children": [{
+                    "type": "Additive",
+                    "children": [
+                        {"type": "Numeric", "value": "1"},
+                        {
+                            "type": "Additive",
+                            "children": [
+                                {"type": "Numeric", "value": "2"},
+                                {"type": "Numeric", "value": "3"}
+                            ]
+                        }
+                    ]
+                }]
+            })
+        );
+        let json = serde_json::to_value(arithmetic::parse("2*3*4").unwrap()).unwrap();
+        assert_eq!(json["children"][0]["children"][1]["type"], "Multiplicative");
+        assert_eq!(json["children"][0]["children"][1]["children"][1]["value"], "4");
+
+        // Tokens serialize like IToken
+        let tokens = arithmetic::tokenize("2+3*4*5").unwrap();
+        assert_eq!(
+            serde_json::to_value(&tokens[..3]).unwrap(),
+            serde_json::json!([
+                {"type": "NUMBER", "value": "2"},
+                {"type": "PLUS", "value": "+"},
+                {"type": "NUMBER", "value": "3"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_precedence_and_associativity() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("10 - 4 - 3", 3.0),
+            ("64 / 8 / 2", 4.0),
+            ("2 * 3 / 4", 1.5),
+            ("-3 - -2", -1.0),
+            ("--4", 4.0),
+            ("-(2 + 3) * 2", -10.0),
+            ("0.5 + .25", 0.75),
+            ("((7))", 7.0),
+            ("8 - 2 + 3 - 1", 8.0),
+            ("1 + 2 - 3 + 4", 4.0),
+            ("12 / 2 * 3 / 6", 3.0),
+            ("2 * 12 / 3 / 2", 4.0),
+        ];
+        for (source_code, value) in cases {
+            assert_eq!(
+                arithmetic::evaluate(&arithmetic::parse(source_code).unwrap()),
+                value,
+                "{}",
+                source_code
+            );
+        }
+        assert_eq!(arithmetic::evaluate(&arithmetic::parse("1/0").unwrap()), f64::INFINITY);
+
+        // Mixed chains fold to the left, giving the same `f64` as JavaScript
+        let js_values = [
+            ("0.1+0.2-0.3", 5.551115123125783e-17),
+            ("0.1*3/3", 0.10000000000000002),
+            ("0.3-0.1+0.2", 0.4),
+            ("1/3*3-1", 0.0),
+            ("0.1+0.2*3-0.3", 0.4000000000000001),
+        ];
+        for (source_code, value) in js_values {
+            assert_eq!(
+                arithmetic::evaluate(&arithmetic::parse(source_code).unwrap()),
+                value,
+                "{}",
+                source_code
+            );
+        }
+
+        // Unary minus binds tighter than `*`, and `-` nests to the left
+        let ast = arithmetic::parse("-2*3-1").unwrap();
+        let negative = ASTNode::with_children(NodeType::Negative, vec![ASTNode::numeric("2")]);
+        let product =
+            ASTNode::with_children(NodeType::Multiplicative, vec![negative, ASTNode::numeric("3")]);
+        assert_eq!(
+            ast.children(),
+            [ASTNode::with_children(NodeType::Subtractive, vec![product, ASTNode::numeric("1")])]
+        );
+
+        let ast = arithmetic::parse("8-2+3").unwrap();
+        let difference =
+            ASTNode::with_children(NodeType::Subtractive, vec![ASTNode::numeric("8"), ASTNode::numeric("2")]);
+        assert_eq!(
+            ast.children(),
+            [ASTNode::with_children(NodeType::Additive, vec![difference, ASTNode::numeric("3")])]
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_errors() {
+        assert_eq!(
+            arithmetic::parse("2 + x").unwrap_err(),
+            ArithmeticError::UnexpectedCharacter { character: 'x', offset: 4 }
+        );
+        assert_eq!(
+            arithmetic::parse("1.").unwrap_err(),
+            ArithmeticError::UnexpectedCharacter { character: '.', offset: 1 }
+        );
+        assert_eq!(
+            arithmetic::parse("2 +").unwrap_err(),
+            ArithmeticError::UnexpectedEndOfInput { offset: 3 }
+        );
+        let err = arithmetic::parse("(1 + 2").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected end of expression");
+        let err = arithmetic::parse("1 2").unwrap_err();
+        assert_eq!(err.span(), Span::new(2, 3));
+        assert_eq!(err.to_string(), "Unexpected NUMBER at 2");
+        assert!(matches!(
+            arithmetic::parse("* 2"),
+            Err(ArithmeticError::UnexpectedToken { token_type: arithmetic::TokenType::Multiply, .. })
+        ));
+        assert!(arithmetic::parse("()").is_err());
+    }
+
+    #[test]
+    fn test_parse_expression_with_jsx() {
+        let Expression::CallExpression(call) = parse_expression("items.map(i => <li/>)").unwrap() else {
+            panic!("expected a call")
+        };
+        assert_eq!(call.span, Span::new(0, 21));
+        let Expression::MemberExpression(callee) = &*call.callee else {
+            panic!("expected a member expression")
+        };
+        assert!(!callee.computed && !callee.optional);
+        let Expression::ArrowFunctionExpression(arrow) = &call.arguments[0] else {
+            panic!("expected an arrow function")
+        };
+        assert_eq!(arrow.params[0].name, "i");
+        let Expression::JSXElement(element) = &*arrow.body else {
+            panic!("expected JSX")
+        };
+        assert_eq!(element.name, "li");
+        assert_eq!(element.span, Span::new(15, 20));
+        assert_eq!(element.closing_span, None);
+
+        // Elements nest, and the expression goes on after the closing tag
+        let source_code = r#"ok ? <ul><li class="a">one</li><br/></ul> : null"#;
+        let Expression::ConditionalExpression(conditional) = parse_expression(source_code).unwrap() else {
+            panic!("expected a conditional")
+        };
+        let Expression::JSXElement(list) = &*conditional.consequent else {
+            panic!("expected JSX")
+        };
+        assert_eq!(&source_code[list.span.start..list.span.end], r#"<ul><li class="a">one</li><br/></ul>"#);
+        assert_eq!(list.children.len(), 2);
+        assert!(matches!(*conditional.alternate, Expression::Literal(_)));
+
+        // Expression containers in embedded JSX are parsed too
+        let json = serde_json::to_value(parse_expression("xs.map(x => <li key={x}>text</li>)").unwrap()).unwrap();
+        let key = &json["arguments"][0]["body"]["attributes"][0]["value"];
+        assert_eq!(key["expression"]["type"], "Identifier");
+        assert_eq!(key["expression"]["span"]["start"], 21);
+    }
+
+    #[test]
+    fn test_parse_expression_operators() {
+        let json = serde_json::to_value(parse_expression("1 + 2 * 3 ** 2 ** 2").unwrap()).unwrap();
+        assert_eq!(json["type"], "BinaryExpression");
+        assert_eq!(json["operator"], "+");
+        assert_eq!(json["right"]["operator"], "*");
+        assert_eq!(json["right"]["right"]["operator"], "**");
+        assert_eq!(json["right"]["right"]["right"]["operator"], "**");
+        assert_eq!(json["right"]["right"]["right"]["left"]["value"], 2.0);
+
+        let json = serde_json::to_value(parse_expression("a - b - c").unwrap()).unwrap();
+        assert_eq!(json["left"]["type"], "BinaryExpression");
+        assert_eq!(json["right"]["name"], "c");
+
+        let json = serde_json::to_value(parse_expression("a || b && !c").unwrap()).unwrap();
+        assert_eq!(json["type"], "LogicalExpression");
+        assert_eq!(json["right"]["operator"], "&&");
+        assert_eq!(json["right"]["right"]["type"], "UnaryExpression");
+        assert_eq!(json["right"]["right"]["prefix"], true);
+
+        let json = serde_json::to_value(parse_expression("typeof x === 'string'").unwrap()).unwrap();
+        assert_eq!(json["operator"], "===");
+        assert_eq!(json["left"]["operator"], "typeof");
+        assert_eq!(json["right"]["raw"], "'string'");
+
+        let json = serde_json::to_value(parse_expression("a ? b : c ? d : e").unwrap()).unwrap();
+        assert_eq!(json["alternate"]["type"], "ConditionalExpression");
+
+        let json = serde_json::to_value(parse_expression("a?.b?.(c)[0]").unwrap()).unwrap();
+        assert_eq!(json["type"], "MemberExpression");
+        assert_eq!(json["computed"], true);
+        assert_eq!(json["object"]["type"], "CallExpression");
+        assert_eq!(json["object"]["optional"], true);
+        assert_eq!(json["object"]["callee"]["optional"], true);
+    }
+
+    #[test]
+    fn test_parse_expression_literals() {
+        let Expression::ObjectExpression(object) = parse_expression("{ a: 1, b, [c]: 2, ...d }").unwrap() else {
+            panic!("expected an object")
+        };
+        assert_eq!(object.properties.len(), 4);
+        assert!(matches!(&object.properties[1], ObjectMember::Property(property) if property.shorthand));
+        assert!(matches!(&object.properties[2], ObjectMember::Property(property) if property.computed));
+        assert!(matches!(&object.properties[3], ObjectMember::SpreadElement(_)));
+
+        let Expression::ArrayExpression(array) = parse_expression("[0x1F, , ...xs]").unwrap() else {
+            panic!("expected an array")
+        };
+        assert!(matches!(
+            &array.elements[0],
+            Some(Expression::Literal(literal)) if literal.value == LiteralValue::Number(31.0) && literal.raw == "0x1F"
+        ));
+        assert_eq!(array.elements[1], None);
+        assert!(matches!(array.elements[2], Some(Expression::SpreadElement(_))));
+
+        assert!(matches!(
+            parse_expression(r#"'it\'s'"#).unwrap(),
+            Expression::Literal(literal) if literal.value == LiteralValue::String("it's".to_string())
+        ));
+
+        let Expression::TemplateLiteral(template) = parse_expression(r"`a${b}c\n`").unwrap() else {
+            panic!("expected a template literal")
+        };
+        assert_eq!(template.quasis.len(), 2);
+        assert_eq!(template.expressions.len(), 1);
+        assert_eq!(template.quasis[1].value.raw, r"c\n");
+        assert_eq!(template.quasis[1].value.cooked, "c\n");
+        assert!(template.quasis[1].tail);
+        assert_eq!(template.span, Span::new(0, 10));
+    }
+
+    #[test]
+    fn test_parse_expression_errors() {
+        assert_eq!(
+            parse_expression("a = 1").unwrap_err(),
+            ExpressionError::Unsupported { construct: "Assignment".to_string(), span: Span::new(2, 3) }
+        );
+        assert_eq!(
+            parse_expression("new Foo()").unwrap_err().to_string(),
+            "`new` is not supported in expressions"
+        );
+        assert!(matches!(parse_expression("\"abc"), Err(ExpressionError::Unterminated { .. })));
+        assert_eq!(
+            parse_expression("a b").unwrap_err(),
+            ExpressionError::UnexpectedToken { token: "b".to_string(), span: Span::new(2, 3) }
+        );
+        assert_eq!(
+            parse_expression("a +").unwrap_err(),
+            ExpressionError::UnexpectedEndOfInput { offset: 3 }
+        );
+        assert_eq!(parse_expression("  ").unwrap_err(), ExpressionError::UnexpectedEndOfInput { offset: 2 });
+        assert_eq!(
+            parse_expression(r#"s.replace(/x/g, "")"#).unwrap_err(),
+            ExpressionError::Unsupported { construct: "A regular expression".to_string(), span: Span::new(10, 11) }
+        );
+        assert!(matches!(
+            parse(r#"<a b={s.replace(/=/g, "")}></a>"#),
+            Err(ParseError::Expression { error: ExpressionError::Unsupported { .. } })
+        ));
+        assert!(parse_expression("a / b / c").is_ok());
+        let err = parse_expression("x && <li>").unwrap_err();
+        assert!(matches!(&err, ExpressionError::Jsx { error } if matches!(**error, ParseError::Nesting { .. })));
+        assert_eq!(err.span(), Span::new(5, 9));
+    }
+
+    #[test]
+    fn test_expression_json_round_trip() {
+        // One source for each node kind
+        let sources = [
+            "a",
+            "this",
+            "null",
+            "true",
+            "1.5",
+            "'s'",
+            "`a${b}c`",
+            "[a, , ...b]",
+            "{ a: 1, b, [c]: d, ...e }",
+            "a.b",
+            "a?.[b]",
+            "f(a, ...b)",
+            "(a, b) => a",
+            "a ? b : c",
+            "a && b ?? c",
+            "a + b * c",
+            "!a",
+            "typeof a",
+            "<p>a</p>",
+            "<ul><li class=\"x\" key={k}>t</li><br/></ul>",
+        ];
+        for source_code in sources {
+            let expression = parse_expression(source_code).unwrap();
+            let json = serde_json::to_value(&expression).unwrap();
+            let back: Expression = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(back, expression, "{}", source_code);
+            assert_eq!(serde_json::to_value(&back).unwrap(), json, "{}", source_code);
+        }
+
+        // Object members come back as the kind they were
+        let Expression::ObjectExpression(object) = parse_expression("{ a: 1, ...b }").unwrap() else {
+            panic!("expected an object")
+        };
+        let json = serde_json::to_string(&object.properties).unwrap();
+        let members: Vec<ObjectMember> = serde_json::from_str(&json).unwrap();
+        assert!(matches!(&members[0], ObjectMember::Property(_)));
+        assert!(matches!(&members[1], ObjectMember::SpreadElement(_)));
+        assert_eq!(members, object.properties);
+
+        // The empty expression of `{}` and parameters keep their type too
+        let root = parse("<p a={}></p>").unwrap();
+        let json = serde_json::to_value(&root).unwrap();
+        assert_eq!(json["attributes"][0]["value"]["expression"]["type"], "JSXEmptyExpression");
+        assert_eq!(serde_json::from_value::<JSXElement>(json).unwrap(), root);
+        let json = serde_json::to_value(parse_expression("x => x").unwrap()).unwrap();
+        assert_eq!(json["params"][0]["type"], "Identifier");
+
+        assert!(serde_json::from_str::<Expression>(r#"{"type":"NewExpression"}"#).is_err());
+        assert!(serde_json::from_str::<Expression>(r#"{"name":"a"}"#).is_err());
+    }
+
+    #[test]
+    fn test_infinite_literal_round_trip() {
+        let expression = parse_expression("[1e400, -1e400, null]").unwrap();
+        let json = serde_json::to_value(&expression).unwrap();
+        // JSON has no infinity; the value is written as null and rebuilt from `raw`
+        assert_eq!(json["elements"][0]["value"], serde_json::Value::Null);
+        let back: Expression = serde_json::from_value(json).unwrap();
+        assert_eq!(back, expression);
+        let Expression::ArrayExpression(array) = back else {
+            panic!("expected an array")
+        };
+        assert!(matches!(
+            &array.elements[0],
+            Some(Expression::Literal(literal)) if literal.value == LiteralValue::Number(f64::INFINITY)
+        ));
+        assert!(matches!(
+            &array.elements[2],
+            Some(Expression::Literal(literal)) if literal.value == LiteralValue::Null
+        ));
+        assert!(serde_json::from_str::<Expression>(
+            r#"{"type":"Literal","value":null,"raw":"x","span":{"start":0,"end":1}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_attribute_expressions() {
+        let source_code = r#"<button onClick={() => select(item.id)} class={active ? "on" : "off"} style={{ color: "red" }} title={"a}b"} label={`n: ${count}`} icon={<img/>}>go</button>"#;
+        let root = parse(source_code).unwrap();
+        let expressions: Vec<&Expression> = root
+            .attributes
+            .iter()
+            .map(|attribute| match attribute {
+                JSXAttributeItem::JSXAttribute(attribute) => match &attribute.value {
+                    Some(JSXAttributeValue::JSXExpressionContainer(container)) => &container.expression,
+                    _ => panic!("expected an expression container"),
+                },
+                JSXAttributeItem::JSXSpreadAttribute(_) => panic!("expected an attribute"),
+            })
+            .collect();
+        assert!(matches!(expressions[0], Expression::ArrowFunctionExpression(_)));
+        assert!(matches!(expressions[1], Expression::ConditionalExpression(_)));
+        assert!(matches!(expressions[2], Expression::ObjectExpression(_)));
+        assert!(matches!(
+            expressions[3],
+            Expression::Literal(literal) if literal.value == LiteralValue::String("a}b".to_string())
+        ));
+        assert!(matches!(expressions[4], Expression::TemplateLiteral(_)));
+        assert!(matches!(expressions[5], Expression::JSXElement(img) if img.closing_span.is_none()));
+        let call = expressions[0].span();
+        assert_eq!(&source_code[call.start..call.end], "() => select(item.id)");
+        assert!(matches!(&root.children[0], JSXChild::JSXText(text) if text.value == "go"));
+        assert_eq!(serde_json::from_value::<JSXElement>(serde_json::to_value(&root).unwrap()).unwrap(), root);
+
+        // The tokenizer itself still takes only letters and digits there
+        assert!(tokenizer(r#"<a b={c.d}></a>"#).is_err());
+        assert!(parse(r#"<a b={c.d}></a>"#).is_ok());
+
+        assert_eq!(
+            parse("<a b={1 +}></a>").unwrap_err(),
+            ParseError::Expression {
+                error: ExpressionError::UnexpectedToken { token: "}".to_string(), span: Span::new(9, 10) }
+            }
+        );
+        assert!(matches!(parse("<a b={x</a>"), Err(ParseError::Expression { .. })));
+        assert!(matches!(parse("<a b={x"), Err(ParseError::Expression { .. })));
+    }
+
+    #[test]
+    fn test_parse_child_expressions() {
+        let root = parse("<div>Hi {user.name}!</div>").unwrap();
+        assert!(matches!(&root.children[0], JSXChild::JSXText(text) if text.value == "Hi "));
+        let JSXChild::JSXExpressionContainer(container) = &root.children[1] else {
+            panic!("expected an expression container")
+        };
+        assert!(matches!(container.expression, Expression::MemberExpression(_)));
+        assert_eq!(container.span, Span::new(8, 19));
+        assert!(matches!(&root.children[2], JSXChild::JSXText(text) if text.value == "!"));
+
+        let root = parse("<p>{a ? b : c}</p>").unwrap();
+        assert_eq!(root.children.len(), 1);
+        assert!(matches!(
+            &root.children[0],
+            JSXChild::JSXExpressionContainer(container)
+                if matches!(container.expression, Expression::ConditionalExpression(_))
+        ));
+
+        let source_code = "<ul>{items.map(i => <li/>)}<br></br>{}</ul>";
+        let root = parse(source_code).unwrap();
+        let JSXChild::JSXExpressionContainer(container) = &root.children[0] else {
+            panic!("expected an expression container")
+        };
+        let Expression::CallExpression(call) = &container.expression else {
+            panic!("expected a call")
+        };
+        assert!(matches!(&call.arguments[0], Expression::ArrowFunctionExpression(_)));
+        assert!(matches!(&root.children[1], JSXChild::JSXElement(br) if br.name == "br"));
+        let json = serde_json::to_value(&root).unwrap();
+        assert_eq!(json["children"][2]["expression"]["type"], "JSXEmptyExpression");
+        assert_eq!(serde_json::from_value::<JSXElement>(json).unwrap(), root);
+
+        // Nested elements read their own containers
+        let root = parse("<a><b>{x}</b>{y}</a>").unwrap();
+        let JSXChild::JSXElement(b) = &root.children[0] else { panic!("expected an element") };
+        assert_eq!(b.children[0].span(), Span::new(6, 9));
+        assert_eq!(root.children[1].span(), Span::new(13, 16));
+
+        assert!(matches!(parse("<p>{a +}</p>"), Err(ParseError::Expression { .. })));
+        assert!(matches!(parse("<p>{a</p>"), Err(ParseError::Expression { .. })));
+    }
 }
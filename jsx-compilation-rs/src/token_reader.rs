@@ -0,0 +1,164 @@
+use crate::span::{Span, SpannedToken};
+use crate::tokenizer::{Token, TokenType};
+use serde::{Deserialize, Serialize};
+
+/// Tokens a [`TokenReader`] can walk: plain [`Token`]s or [`SpannedToken`]s
+pub trait AsToken {
+    fn as_token(&self) -> &Token;
+
+    /// Where the token is in the source, if known
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+impl AsToken for Token {
+    fn as_token(&self) -> &Token {
+        self
+    }
+}
+
+impl AsToken for SpannedToken {
+    fn as_token(&self) -> &Token {
+        &self.token
+    }
+
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// Returned by [`TokenReader::expect`] when the next token is not the one expected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnexpectedToken {
+    pub expected: TokenType,
+    /// The type of the token found, `None` at the end of the tokens
+    pub found: Option<TokenType>,
+    /// Index of the token found
+    pub index: usize,
+    /// Span of the token found, when reading [`SpannedToken`]s
+    pub span: Option<Span>,
+}
+
+impl std::fmt::Display for UnexpectedToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(found) => write!(f, "Expected {} but found {}", self.expected, found),
+            None => write!(f, "Expected {} but reached the end of input", self.expected),
+        }
+    }
+}
+
+impl std::error::Error for UnexpectedToken {}
+
+/// A position to return to with [`TokenReader::rewind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReaderCheckpoint(usize);
+
+/// Cursor over a token slice, the Rust counterpart of `TokenReader.ts`
+///
+/// `read`, `peek` and `unread` behave as in TypeScript, returning `None`
+/// where it returns `null`. On top of those, `peek_n` looks further ahead,
+/// `expect` consumes a token of a given type or reports what was found, and
+/// `checkpoint`/`rewind` let a parser try one reading and back out of it.
+///
+/// ```rust
+/// use jsx_compilation_rs::{tokenizer, TokenReader, TokenType};
+///
+/// let tokens = tokenizer("<p>Hi</p>").unwrap();
+/// let mut reader = TokenReader::new(&tokens);
+/// reader.expect(TokenType::LeftParentheses).unwrap();
+/// assert_eq!(reader.expect(TokenType::JSXIdentifier).unwrap().value, "p");
+///
+/// let checkpoint = reader.checkpoint();
+/// let err = reader.expect(TokenType::JSXText).unwrap_err();
+/// assert_eq!(err.to_string(), "Expected JSXText but found RightParentheses");
+/// reader.read();
+/// assert_eq!(reader.peek_n(1).unwrap().token_type, TokenType::LeftParentheses);
+/// reader.rewind(checkpoint);
+/// assert_eq!(reader.position(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenReader<'a, T = Token> {
+    tokens: &'a [T],
+    position: usize,
+}
+
+impl<'a, T: AsToken> TokenReader<'a, T> {
+    pub fn new(tokens: &'a [T]) -> Self {
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+
+    /// Index of the next token to be read
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether every token has been read
+    pub fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// Consume and return the next token
+    pub fn read(&mut self) -> Option<&'a T> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    /// The next token, without consuming it
+    pub fn peek(&self) -> Option<&'a T> {
+        self.peek_n(0)
+    }
+
+    /// The token `n` places after the next one; `peek_n(0)` is [`peek`](Self::peek)
+    pub fn peek_n(&self, n: usize) -> Option<&'a T> {
+        self.tokens.get(self.position + n)
+    }
+
+    /// The type of the token `n` places after the next one
+    pub fn peek_type(&self, n: usize) -> Option<TokenType> {
+        self.peek_n(n).map(|token| token.as_token().token_type)
+    }
+
+    /// Step back one token; does nothing at the start
+    pub fn unread(&mut self) {
+        self.position = self.position.saturating_sub(1);
+    }
+
+    /// Consume the next token if it is of type `expected`
+    ///
+    /// Otherwise nothing is consumed, and the error says what was found.
+    pub fn expect(&mut self, expected: TokenType) -> Result<&'a T, UnexpectedToken> {
+        match self.peek() {
+            Some(token) if token.as_token().token_type == expected => {
+                self.position += 1;
+                Ok(token)
+            }
+            found => Err(UnexpectedToken {
+                expected,
+                found: found.map(|token| token.as_token().token_type),
+                index: self.position,
+                span: found.and_then(AsToken::span),
+            }),
+        }
+    }
+
+    /// Remember the current position
+    pub fn checkpoint(&self) -> ReaderCheckpoint {
+        ReaderCheckpoint(self.position)
+    }
+
+    /// Return to a position saved by [`checkpoint`](Self::checkpoint)
+    pub fn rewind(&mut self, checkpoint: ReaderCheckpoint) {
+        self.position = checkpoint.0;
+    }
+
+    /// The tokens not read yet
+    pub fn remaining(&self) -> &'a [T] {
+        &self.tokens[self.position.min(self.tokens.len())..]
+    }
+}
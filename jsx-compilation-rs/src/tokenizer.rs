@@ -1,7 +1,11 @@
+use crate::dfa::{Collect, Emit, Flush, Table};
+use crate::diagnostic::{Diagnostic, FailureContext};
+use crate::locale::{Locale, LocalizedError};
+use crate::span::{Span, SpannedToken};
 use serde::{Deserialize, Serialize};
 
 /// Token types that can be recognized in JSX
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TokenType {
     LeftParentheses,
     JSXIdentifier,
@@ -44,27 +48,71 @@ impl Token {
 }
 
 /// Error types for tokenization
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serialized as `{"kind": "UnexpectedCharacter", "character": "@"}`; the
+/// `character` field is omitted for variants without a payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "character")]
 pub enum TokenizerError {
     InvalidFirstCharacter,
     UnexpectedCharacter(char),
     UnexpectedEndOfInput,
 }
 
-impl std::fmt::Display for TokenizerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl TokenizerError {
+    /// Stable error code, unaffected by changes to the message text
+    pub fn code(&self) -> &'static str {
         match self {
-            TokenizerError::InvalidFirstCharacter => write!(f, "第一个字符必须是<"),
-            TokenizerError::UnexpectedCharacter(ch) => write!(f, "Unexpected character: {}", ch),
-            TokenizerError::UnexpectedEndOfInput => write!(f, "Unexpected end of input"),
+            TokenizerError::InvalidFirstCharacter => "JSX0001",
+            TokenizerError::UnexpectedCharacter(_) => "JSX0002",
+            TokenizerError::UnexpectedEndOfInput => "JSX0003",
+        }
+    }
+
+    /// Machine-readable form of the error with its message in the given locale
+    pub fn report(&self, locale: Locale) -> ErrorReport {
+        ErrorReport {
+            code: self.code().to_string(),
+            message: self.message(locale),
+            error: self.clone(),
         }
     }
+
+    /// Error message in the given locale
+    pub fn message(&self, locale: Locale) -> String {
+        locale.format_error(self)
+    }
+
+    /// Wrap the error so that it displays in the given locale
+    pub fn localized(&self, locale: Locale) -> LocalizedError<'_> {
+        LocalizedError::new(self, locale)
+    }
+}
+
+/// Displays the message in the default locale (English)
+impl std::fmt::Display for TokenizerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.localized(Locale::default()))
+    }
 }
 
 impl std::error::Error for TokenizerError {}
 
+/// Serializable error report: stable code, localized message and the error itself
+///
+/// ```json
+/// {"code": "JSX0002", "message": "Unexpected character: @", "kind": "UnexpectedCharacter", "character": "@"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub code: String,
+    pub message: String,
+    #[serde(flatten)]
+    pub error: TokenizerError,
+}
+
 /// State enum for the finite state machine
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum State {
     Start,
     FoundLeftParentheses,
@@ -77,214 +125,427 @@ pub enum State {
     TryLeaveAttribute,
     FoundRightParentheses,
     JSXText,
+    /// Saw the `/` of a self-closing `/>` (only reachable when self-closing tags are enabled)
+    SelfClosingSlash,
+}
+
+/// Serializable snapshot of a [`Tokenizer`] mid-input
+///
+/// Holds everything needed to continue lexing at [`offset`](Checkpoint::offset):
+/// the state, the partially read token and the nesting depth.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    state: State,
+    current_token: Token,
+    offset: usize,
+    token_start: usize,
+    depth: usize,
+    in_closing_tag: bool,
+    self_closing: bool,
+}
+
+impl Checkpoint {
+    /// State the tokenizer was in
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Byte offset of the first character not yet processed
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Number of elements opened and not yet closed
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The token being read when the checkpoint was taken, if any
+    pub fn partial_token(&self) -> Option<&Token> {
+        (!self.current_token.value.is_empty()).then_some(&self.current_token)
+    }
 }
 
 /// JSX Tokenizer using finite state machine
 pub struct Tokenizer {
     tokens: Vec<Token>,
+    spans: Vec<Span>,
     current_token: Token,
     state: State,
+    /// Byte offset of the character being processed
+    offset: usize,
+    /// Byte offset where `current_token` started
+    token_start: usize,
+    /// Accept `<img/>`, which the TypeScript reference rejects
+    self_closing: bool,
+    /// Number of elements opened and not yet closed
+    depth: usize,
+    /// Whether the tag being read is a closing tag
+    in_closing_tag: bool,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Tokenizer {
     pub fn new() -> Self {
         Self {
             tokens: Vec::new(),
+            spans: Vec::new(),
             current_token: Token::new(TokenType::JSXText, String::new()),
             state: State::Start,
+            offset: 0,
+            token_start: 0,
+            self_closing: false,
+            depth: 0,
+            in_closing_tag: false,
         }
     }
 
+    /// Snapshot the tokenizer so that lexing can be resumed later
+    ///
+    /// Tokens already emitted are not part of the checkpoint.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            state: self.state,
+            current_token: self.current_token.clone(),
+            offset: self.offset,
+            token_start: self.token_start,
+            depth: self.depth,
+            in_closing_tag: self.in_closing_tag,
+            self_closing: self.self_closing,
+        }
+    }
+
+    /// A tokenizer that continues from a checkpoint, with no tokens emitted yet
+    pub fn from_checkpoint(checkpoint: Checkpoint) -> Self {
+        Self {
+            current_token: checkpoint.current_token,
+            state: checkpoint.state,
+            offset: checkpoint.offset,
+            token_start: checkpoint.token_start,
+            self_closing: checkpoint.self_closing,
+            depth: checkpoint.depth,
+            in_closing_tag: checkpoint.in_closing_tag,
+            ..Self::new()
+        }
+    }
+
+    /// A tokenizer resuming at byte `offset` between tags, where no token is in
+    /// progress: just after a `>` or just before a `<`
+    pub(crate) fn resume_between_tags(offset: usize) -> Self {
+        Self {
+            state: State::FoundRightParentheses,
+            offset,
+            ..Self::new()
+        }
+    }
+
+    /// A tokenizer that also accepts self-closing tags, emitting `/>` as
+    /// `BackSlash` followed by `RightParentheses`
+    pub(crate) fn with_self_closing() -> Self {
+        Self {
+            self_closing: true,
+            ..Self::new()
+        }
+    }
+
+    /// A self-closing tokenizer starting at byte `offset` of a larger source,
+    /// for JSX embedded in an expression
+    pub(crate) fn embedded(offset: usize) -> Self {
+        Self {
+            self_closing: true,
+            offset,
+            ..Self::new()
+        }
+    }
+
+    /// Number of elements opened and not yet closed
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// State the next character will be processed in
+    pub(crate) fn state(&self) -> State {
+        self.state
+    }
+
+    /// Add `text` to the token being read without running the state machine
+    ///
+    /// For code the parser has already found the end of, which the state
+    /// machine would reject.
+    pub(crate) fn push_raw(&mut self, text: &str) {
+        self.current_token.value.push_str(text);
+        self.offset += text.len();
+    }
+
+    /// Skip `len` bytes between tags without running the state machine,
+    /// ending any text before them
+    ///
+    /// For the expression containers among children, which the parser reads
+    /// itself.
+    pub(crate) fn skip_between_tags(&mut self, len: usize) {
+        if self.state == State::JSXText {
+            self.emit_current(self.current_span(self.offset));
+            self.state = State::FoundRightParentheses;
+        }
+        self.offset += len;
+    }
+
     /// Emit a token and reset current token
-    fn emit(&mut self, token: Token) {
+    fn emit(&mut self, token: Token, span: Span) {
         self.current_token = Token::new(TokenType::JSXText, String::new());
         self.tokens.push(token);
+        self.spans.push(span);
     }
 
-    /// Check if character is a letter or digit
-    fn is_letter_or_digit(ch: char) -> bool {
-        ch.is_ascii_alphanumeric()
+    /// Emit `ch` as a token of its own
+    fn emit_char(&mut self, token_type: TokenType, ch: char) {
+        self.emit(Token::new(token_type, ch.to_string()), self.char_span(ch));
     }
 
-    /// Process a character in the current state
-    fn process_char(&mut self, ch: char) -> Result<(), TokenizerError> {
-        self.state = match self.state {
-            State::Start => self.handle_start(ch)?,
-            State::FoundLeftParentheses => self.handle_found_left_parentheses(ch)?,
-            State::JSXIdentifier => self.handle_jsx_identifier(ch)?,
-            State::Attribute => self.handle_attribute(ch)?,
-            State::AttributeKey => self.handle_attribute_key(ch)?,
-            State::AttributeValue => self.handle_attribute_value(ch)?,
-            State::AttributeStringValue => self.handle_attribute_string_value(ch)?,
-            State::AttributeExpressionValue => self.handle_attribute_expression_value(ch)?,
-            State::TryLeaveAttribute => self.handle_try_leave_attribute(ch)?,
-            State::FoundRightParentheses => self.handle_found_right_parentheses(ch)?,
-            State::JSXText => self.handle_jsx_text(ch)?,
-        };
-        Ok(())
+    /// Emit the in-progress token, moving it out rather than copying it
+    fn emit_current(&mut self, span: Span) {
+        let token = std::mem::replace(
+            &mut self.current_token,
+            Token::new(TokenType::JSXText, String::new()),
+        );
+        self.emit(token, span);
     }
 
-    /// Tokenize the input string
-    pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizerError> {
-        let mut tokenizer = Tokenizer::new();
+    /// Span of the in-progress token, ending (exclusively) at `end`
+    fn current_span(&self, end: usize) -> Span {
+        Span::new(self.token_start, end)
+    }
 
-        for ch in input.chars() {
-            tokenizer.process_char(ch)?;
-        }
+    /// Span of the character being processed
+    fn char_span(&self, ch: char) -> Span {
+        Span::new(self.offset, self.offset + ch.len_utf8())
+    }
 
-        Ok(tokenizer.tokens)
+    /// Process a character in the current state
+    pub(crate) fn process_char(&mut self, ch: char) -> Result<(), TokenizerError> {
+        self.step(Table::get(self.self_closing), ch)
     }
 
-    /// Initial state - expects '<'
-    fn handle_start(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if ch == '<' {
-            self.emit(Token::new(TokenType::LeftParentheses, "<".to_string()));
-            Ok(State::FoundLeftParentheses)
-        } else {
-            Err(TokenizerError::InvalidFirstCharacter)
+    /// Take the transition `table` gives for `ch` in the current state
+    #[inline(always)]
+    fn step(&mut self, table: &Table, ch: char) -> Result<(), TokenizerError> {
+        let transition = table.transition(self.state, ch)?;
+        // A closing tag cannot also be self-closing, as in `</a/>`
+        if self.in_closing_tag && transition.next == State::SelfClosingSlash {
+            return Err(TokenizerError::UnexpectedCharacter(ch));
         }
+        match transition.flush {
+            Flush::None => {}
+            Flush::Before => self.emit_current(self.current_span(self.offset)),
+            Flush::Through => self.emit_current(self.current_span(self.offset + ch.len_utf8())),
+            Flush::Empty => self.emit_current(Span::new(self.offset, self.offset)),
+        }
+        match transition.collect {
+            Collect::None => {}
+            Collect::Begin(token_type) => {
+                self.current_token.token_type = token_type;
+                self.current_token.value.push(ch);
+                self.token_start = self.offset;
+            }
+            Collect::Open(token_type) => {
+                self.current_token.token_type = token_type;
+                self.current_token.value.clear();
+                self.token_start = self.offset;
+            }
+            Collect::Push => self.current_token.value.push(ch),
+        }
+        match transition.emit {
+            Emit::None => {}
+            Emit::Punct(token_type) => {
+                self.emit_char(token_type, ch);
+                // The `>` of `/>` ends a tag too, leaving the depth as it was
+                if token_type == TokenType::RightParentheses {
+                    self.in_closing_tag = false;
+                }
+            }
+            Emit::TagEnd => {
+                self.emit_char(TokenType::RightParentheses, ch);
+                if self.in_closing_tag {
+                    self.depth = self.depth.saturating_sub(1);
+                    self.in_closing_tag = false;
+                } else {
+                    self.depth += 1;
+                }
+            }
+        }
+        if transition.closing_tag {
+            self.in_closing_tag = true;
+        }
+        self.state = transition.next;
+        self.offset += ch.len_utf8();
+        Ok(())
     }
 
-    /// After finding '<', expect identifier or '/'
-    fn handle_found_left_parentheses(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.token_type = TokenType::JSXIdentifier;
-            self.current_token.value.push(ch);
-            Ok(State::JSXIdentifier)
-        } else if ch == '/' {
-            self.emit(Token::new(TokenType::BackSlash, "/".to_string()));
-            Ok(State::FoundLeftParentheses)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
+    /// Process a run of input; equivalent to [`process_char`](Self::process_char)
+    /// for each character, but faster
+    ///
+    /// Once a text, identifier or value token has started, the rest of it is
+    /// found by scanning the bytes for the first one that ends it and appended
+    /// with a single copy, so the state machine only sees delimiters.
+    pub(crate) fn process_str(&mut self, input: &str) -> Result<(), TokenizerError> {
+        let table = Table::get(self.self_closing);
+        let mut rest = input;
+        while let Some(ch) = rest.chars().next() {
+            let run = table.run_len(self.state, rest.as_bytes());
+            if run > 0 {
+                self.current_token.value.push_str(&rest[..run]);
+                self.offset += run;
+                rest = &rest[run..];
+            } else {
+                self.step(table, ch)?;
+                rest = &rest[ch.len_utf8()..];
+            }
         }
+        Ok(())
     }
 
-    /// Collecting JSX identifier characters
-    fn handle_jsx_identifier(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.value.push(ch);
-            Ok(State::JSXIdentifier)
-        } else if ch == ' ' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            Ok(State::Attribute)
-        } else if ch == '>' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            self.emit(Token::new(TokenType::RightParentheses, ">".to_string()));
-            Ok(State::FoundRightParentheses)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
-        }
+    /// Move the tokens emitted so far out of the tokenizer, keeping the buffer
+    pub(crate) fn drain_tokens(&mut self) -> std::vec::Drain<'_, Token> {
+        self.spans.clear();
+        self.tokens.drain(..)
     }
 
-    /// Looking for attribute key
-    fn handle_attribute(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.token_type = TokenType::AttributeKey;
-            self.current_token.value.push(ch);
-            Ok(State::AttributeKey)
-        } else if ch == '=' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            Ok(State::AttributeValue)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
-        }
+    /// Byte offset of the next character to be processed
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
     }
 
-    /// Collecting attribute key characters
-    fn handle_attribute_key(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.value.push(ch);
-            Ok(State::AttributeKey)
-        } else if ch == '=' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            Ok(State::AttributeValue)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
+    /// Validate that the input may end in the current state
+    ///
+    /// Text still being collected after the last tag is emitted as a final
+    /// `JSXText` token; any other unfinished construct is an
+    /// `UnexpectedEndOfInput` error. Empty input is accepted.
+    pub(crate) fn finish(&mut self) -> Result<(), TokenizerError> {
+        match self.state {
+            State::Start | State::FoundRightParentheses => Ok(()),
+            State::JSXText => {
+                self.emit_current(self.current_span(self.offset));
+                self.state = State::FoundRightParentheses;
+                Ok(())
+            }
+            _ => Err(TokenizerError::UnexpectedEndOfInput),
         }
     }
 
-    /// Expecting attribute value (string or expression)
-    fn handle_attribute_value(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if ch == '"' {
-            self.current_token.token_type = TokenType::AttributeStringValue;
-            self.current_token.value.clear();
-            Ok(State::AttributeStringValue)
-        } else if ch == '{' {
-            self.current_token.token_type = TokenType::AttributeExpressionValue;
-            self.current_token.value.clear();
-            Ok(State::AttributeExpressionValue)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
-        }
+    /// Tokenize the input string
+    pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizerError> {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.process_str(input)?;
+        Ok(tokenizer.tokens)
     }
 
-    /// Collecting string attribute value
-    fn handle_attribute_string_value(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.value.push(ch);
-            Ok(State::AttributeStringValue)
-        } else if ch == '"' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            Ok(State::TryLeaveAttribute)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
-        }
+    /// Return to the start state, keeping allocated buffers
+    ///
+    /// Whether self-closing tags are accepted is kept too.
+    pub fn reset(&mut self) {
+        self.tokens.clear();
+        self.spans.clear();
+        self.current_token.value.clear();
+        self.state = State::Start;
+        self.offset = 0;
+        self.token_start = 0;
+        self.depth = 0;
+        self.in_closing_tag = false;
     }
 
-    /// Collecting expression attribute value
-    fn handle_attribute_expression_value(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if Self::is_letter_or_digit(ch) {
-            self.current_token.value.push(ch);
-            Ok(State::AttributeExpressionValue)
-        } else if ch == '}' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            Ok(State::TryLeaveAttribute)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
+    /// Tokenize the input string, appending the tokens to `out`
+    ///
+    /// Resets the tokenizer first, so one instance can be reused across
+    /// inputs without reallocating its buffers; clear `out` between calls to
+    /// reuse its capacity as well. On failure `out` is left as it was.
+    ///
+    /// ```rust
+    /// use jsx_compilation_rs::{Tokenizer, TokenType};
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// let mut tokens = Vec::new();
+    /// for source in ["<p>one</p>", "<p>two</p>", "<p>three</p>"] {
+    ///     tokens.clear();
+    ///     tokenizer.tokenize_into(source, &mut tokens).unwrap();
+    ///     assert_eq!(tokens[3].token_type, TokenType::JSXText);
+    /// }
+    /// assert_eq!(tokens[3].value, "three");
+    /// assert!(tokenizer.tokenize_into("<p class=x>", &mut tokens).is_err());
+    /// assert_eq!(tokens.len(), 8);
+    /// ```
+    pub fn tokenize_into(
+        &mut self,
+        input: &str,
+        out: &mut Vec<Token>,
+    ) -> Result<(), TokenizerError> {
+        self.reset();
+        let len = out.len();
+        std::mem::swap(&mut self.tokens, out);
+        let result = self.process_str(input);
+        std::mem::swap(&mut self.tokens, out);
+        self.spans.clear();
+        if result.is_err() {
+            out.truncate(len);
         }
+        result
     }
 
-    /// After attribute value, decide next state
-    fn handle_try_leave_attribute(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if ch == ' ' {
-            Ok(State::Attribute)
-        } else if ch == '>' {
-            self.emit(Token::new(TokenType::RightParentheses, ">".to_string()));
-            Ok(State::FoundRightParentheses)
-        } else {
-            Err(TokenizerError::UnexpectedCharacter(ch))
-        }
+    /// Tokenize the input string, keeping the source span of every token
+    ///
+    /// On failure the error is returned as a [`Diagnostic`] locating the
+    /// offending character, with fix-it suggestions where the mistake is a
+    /// common one.
+    pub fn tokenize_spanned(input: &str) -> Result<Vec<SpannedToken>, Diagnostic> {
+        Tokenizer::new().run_spanned(input)
     }
 
-    /// After '>', expect text or new element
-    fn handle_found_right_parentheses(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if ch == '<' {
-            self.emit(Token::new(TokenType::LeftParentheses, "<".to_string()));
-            Ok(State::FoundLeftParentheses)
-        } else {
-            self.current_token.token_type = TokenType::JSXText;
-            self.current_token.value.push(ch);
-            Ok(State::JSXText)
-        }
+    pub(crate) fn run_spanned(self, input: &str) -> Result<Vec<SpannedToken>, Diagnostic> {
+        let mut tokenizer = self;
+        tokenizer
+            .process_str(input)
+            .map_err(|error| tokenizer.diagnose(input, error))?;
+        Ok(tokenizer.drain_spanned().collect())
     }
 
-    /// Collecting JSX text content
-    fn handle_jsx_text(&mut self, ch: char) -> Result<State, TokenizerError> {
-        if ch == '<' {
-            let token = self.current_token.clone();
-            self.emit(token);
-            self.emit(Token::new(TokenType::LeftParentheses, "<".to_string()));
-            Ok(State::FoundLeftParentheses)
-        } else {
-            self.current_token.value.push(ch);
-            Ok(State::JSXText)
-        }
+    /// Process a character of `source`, reporting failure as a [`Diagnostic`]
+    ///
+    /// `source` is the whole input, so that suggestions can look around the
+    /// offending character.
+    pub(crate) fn process_char_diagnosed(
+        &mut self,
+        source: &str,
+        ch: char,
+    ) -> Result<(), Diagnostic> {
+        self.process_char(ch)
+            .map_err(|error| self.diagnose(source, error))
+    }
+
+    /// Turn the error from the character at the current offset into a [`Diagnostic`]
+    ///
+    /// A failed character leaves the state and offset as they were, so they
+    /// still describe where lexing stopped.
+    pub(crate) fn diagnose(&self, source: &str, error: TokenizerError) -> Diagnostic {
+        let ch = source[self.offset..].chars().next().unwrap_or_default();
+        let context = FailureContext {
+            input: source,
+            state: self.state,
+            offset: self.offset,
+            ch,
+        };
+        Diagnostic::new(error, self.char_span(ch)).with_suggestions(context.suggestions())
+    }
+
+    /// Move the tokens emitted so far out of the tokenizer, together with their spans
+    pub(crate) fn drain_spanned(&mut self) -> impl Iterator<Item = SpannedToken> + '_ {
+        self.tokens
+            .drain(..)
+            .zip(self.spans.drain(..))
+            .map(|(token, span)| SpannedToken::new(token, span))
     }
 }
 
@@ -292,3 +553,8 @@ impl Tokenizer {
 pub fn tokenizer(input: &str) -> Result<Vec<Token>, TokenizerError> {
     Tokenizer::tokenize(input)
 }
+
+/// Tokenize JSX, keeping token spans and reporting failures as a [`Diagnostic`]
+pub fn tokenize_spanned(input: &str) -> Result<Vec<SpannedToken>, Diagnostic> {
+    Tokenizer::tokenize_spanned(input)
+}
@@ -0,0 +1,26 @@
+use crate::dfa::Table;
+use crate::tokenizer::TokenizerError;
+use std::ops::ControlFlow;
+
+/// Check JSX without building tokens
+///
+/// Drives the same state machine as [`tokenizer`](crate::tokenizer) but keeps
+/// nothing except positions in the input, so it never allocates. Text and value
+/// runs are skipped in bulk. Accepts exactly the inputs `tokenizer` accepts
+/// and fails with the same error; the `usize` is the byte offset of the
+/// offending character.
+///
+/// ```rust
+/// use jsx_compilation_rs::{validate, TokenizerError};
+///
+/// assert_eq!(validate(r#"<p class="intro">Hello</p>"#), Ok(()));
+/// assert_eq!(
+///     validate("<p class=intro>Hello</p>"),
+///     Err((TokenizerError::UnexpectedCharacter('i'), 9))
+/// );
+/// ```
+pub fn validate(input: &str) -> Result<(), (TokenizerError, usize)> {
+    Table::get(false)
+        .drive(input, |_| ControlFlow::Continue(()))
+        .map(|_| ())
+}
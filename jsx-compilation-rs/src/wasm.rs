@@ -1,5 +1,8 @@
 use wasm_bindgen::prelude::*;
-use crate::tokenizer::{tokenizer as rust_tokenizer, Token, TokenType, TokenizerError};
+use crate::budget::{tokenize_budgeted, Budget, Progress};
+use crate::locale::Locale;
+use crate::push::PushTokenizer;
+use crate::tokenizer::{tokenizer as rust_tokenizer, Token};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global allocator.
 #[cfg(feature = "wee_alloc")]
@@ -41,39 +44,155 @@ impl From<Token> for WasmToken {
     }
 }
 
+/// Resolve the optional locale tag passed from JavaScript, defaulting to English
+fn resolve_locale(locale: Option<String>) -> Result<Locale, JsValue> {
+    match locale {
+        Some(tag) => tag
+            .parse::<Locale>()
+            .map_err(|e| JsValue::from_str(&e.to_string())),
+        None => Ok(Locale::default()),
+    }
+}
+
+/// Convert tokens into an array of `{ type, value }` JavaScript objects
+fn tokens_to_array(tokens: Vec<Token>) -> Result<js_sys::Array, JsValue> {
+    let js_array = js_sys::Array::new();
+    for token in tokens {
+        let js_object = js_sys::Object::new();
+        js_sys::Reflect::set(
+            &js_object,
+            &JsValue::from_str("type"),
+            &JsValue::from_str(&token.token_type.to_string()),
+        )?;
+        js_sys::Reflect::set(
+            &js_object,
+            &JsValue::from_str("value"),
+            &JsValue::from_str(&token.value),
+        )?;
+        js_array.push(&js_object);
+    }
+    Ok(js_array)
+}
+
 /// Tokenize JSX input and return tokens as JavaScript objects
+///
+/// Error messages are reported in `locale` (`"en"` or `"zh-CN"`), English by default.
 #[wasm_bindgen]
-pub fn tokenizer(input: &str) -> Result<js_sys::Array, JsValue> {
+pub fn tokenizer(input: &str, locale: Option<String>) -> Result<js_sys::Array, JsValue> {
     // Set panic hook for better error messages
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 
+    let locale = resolve_locale(locale)?;
     match rust_tokenizer(input) {
-        Ok(tokens) => {
-            let js_array = js_sys::Array::new();
-            for token in tokens {
-                let js_object = js_sys::Object::new();
-                js_sys::Reflect::set(
-                    &js_object,
-                    &JsValue::from_str("type"),
-                    &JsValue::from_str(&token.token_type.to_string()),
-                )?;
-                js_sys::Reflect::set(
-                    &js_object,
-                    &JsValue::from_str("value"),
-                    &JsValue::from_str(&token.value),
-                )?;
-                js_array.push(&js_object);
-            }
-            Ok(js_array)
+        Ok(tokens) => tokens_to_array(tokens),
+        Err(e) => Err(JsValue::from_str(&e.message(locale))),
+    }
+}
+
+/// Chunked tokenizer for JSX that arrives piece by piece, e.g. from an editor or a pipe
+#[wasm_bindgen]
+pub struct WasmPushTokenizer {
+    inner: Option<PushTokenizer>,
+    locale: Locale,
+}
+
+#[wasm_bindgen]
+impl WasmPushTokenizer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(locale: Option<String>) -> Result<WasmPushTokenizer, JsValue> {
+        Ok(WasmPushTokenizer {
+            inner: Some(PushTokenizer::new()),
+            locale: resolve_locale(locale)?,
+        })
+    }
+
+    /// Feed the next chunk and return the tokens it completed
+    pub fn feed(&mut self, chunk: &str) -> Result<js_sys::Array, JsValue> {
+        let inner = self
+            .inner
+            .as_mut()
+            .ok_or_else(|| JsValue::from_str("Tokenizer already finished"))?;
+        match inner.feed(chunk) {
+            Ok(tokens) => tokens_to_array(tokens),
+            Err(e) => Err(JsValue::from_str(&e.message(self.locale))),
+        }
+    }
+
+    /// End the input, returning the final tokens
+    pub fn finish(&mut self) -> Result<js_sys::Array, JsValue> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or_else(|| JsValue::from_str("Tokenizer already finished"))?;
+        match inner.finish() {
+            Ok(tokens) => tokens_to_array(tokens),
+            Err(e) => Err(JsValue::from_str(&e.message(self.locale))),
+        }
+    }
+}
+
+/// Tokenizer that works through a large input a slice at a time, so the page
+/// stays responsive
+///
+/// Call `run(steps)` from `requestAnimationFrame` or a timer until it returns
+/// `true`, then read the tokens with `tokens()`.
+#[wasm_bindgen]
+pub struct WasmBudgetedTokenizer {
+    input: String,
+    progress: Option<Progress>,
+    locale: Locale,
+}
+
+#[wasm_bindgen]
+impl WasmBudgetedTokenizer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: String, locale: Option<String>) -> Result<WasmBudgetedTokenizer, JsValue> {
+        Ok(WasmBudgetedTokenizer {
+            input,
+            progress: None,
+            locale: resolve_locale(locale)?,
+        })
+    }
+
+    /// Lex up to `steps` more bytes, returning whether the input is finished
+    pub fn run(&mut self, steps: usize) -> Result<bool, JsValue> {
+        let budget = Budget::steps(steps);
+        let progress = match self.progress.take() {
+            None => tokenize_budgeted(&self.input, budget),
+            Some(Progress::Paused(paused)) => paused.resume(&self.input, budget),
+            Some(done) => Ok(done),
+        }
+        .map_err(|diagnostic| JsValue::from_str(&diagnostic.message(self.locale)))?;
+        let done = matches!(progress, Progress::Done(_));
+        self.progress = Some(progress);
+        Ok(done)
+    }
+
+    /// Byte offset reached so far
+    pub fn offset(&self) -> usize {
+        match &self.progress {
+            None => 0,
+            Some(Progress::Paused(paused)) => paused.offset(),
+            Some(Progress::Done(_)) => self.input.len(),
         }
-        Err(e) => Err(JsValue::from_str(&e.to_string())),
+    }
+
+    /// The tokens completed so far
+    pub fn tokens(&self) -> Result<js_sys::Array, JsValue> {
+        let tokens = match &self.progress {
+            None => &[][..],
+            Some(Progress::Paused(paused)) => paused.tokens(),
+            Some(Progress::Done(tokens)) => tokens,
+        };
+        tokens_to_array(tokens.to_vec())
     }
 }
 
 /// Tokenize JSX input and return JSON string
 #[wasm_bindgen]
-pub fn tokenize_to_json(input: &str) -> Result<String, JsValue> {
+pub fn tokenize_to_json(input: &str, locale: Option<String>) -> Result<String, JsValue> {
+    let locale = resolve_locale(locale)?;
     match rust_tokenizer(input) {
         Ok(tokens) => {
             match serde_json::to_string(&tokens) {
@@ -81,14 +200,56 @@ pub fn tokenize_to_json(input: &str) -> Result<String, JsValue> {
                 Err(e) => Err(JsValue::from_str(&e.to_string())),
             }
         }
-        Err(e) => Err(JsValue::from_str(&e.to_string())),
+        Err(e) => Err(JsValue::from_str(&e.message(locale))),
+    }
+}
+
+/// Tokenize JSX input and return the error as a JSON report, or `undefined` if it is valid
+///
+/// The report has a stable `code` (e.g. `"JSX0002"`) alongside the localized `message`.
+#[wasm_bindgen]
+pub fn tokenize_error_json(input: &str, locale: Option<String>) -> Result<Option<String>, JsValue> {
+    let locale = resolve_locale(locale)?;
+    match rust_tokenizer(input) {
+        Ok(_) => Ok(None),
+        Err(e) => crate::error_to_json(&e, locale)
+            .map(Some)
+            .map_err(|e| JsValue::from_str(&e.to_string())),
+    }
+}
+
+/// Tokenize JSX input into role-labelled tokens (`OpeningTagStart`, `ClosingTagStart`, ...)
+/// and return them as a JSON string
+#[wasm_bindgen]
+pub fn tokenize_roles_to_json(input: &str, locale: Option<String>) -> Result<String, JsValue> {
+    let locale = resolve_locale(locale)?;
+    match crate::roles::tokenize_roles(input) {
+        Ok(tokens) => {
+            serde_json::to_string(&tokens).map_err(|e| JsValue::from_str(&e.to_string()))
+        }
+        Err(diagnostic) => Err(JsValue::from_str(&diagnostic.message(locale))),
     }
 }
 
-/// Check if JSX syntax is valid
+/// Parse an arithmetic expression and return its tree as JSON, shaped like `parser.ts` output
+#[wasm_bindgen]
+pub fn parse_arithmetic_to_json(input: &str) -> Result<String, JsValue> {
+    let ast = crate::arithmetic::parse(input).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    serde_json::to_string(&ast).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Parse and evaluate an arithmetic expression
+#[wasm_bindgen]
+pub fn evaluate_arithmetic(input: &str) -> Result<f64, JsValue> {
+    crate::arithmetic::parse(input)
+        .map(|ast| crate::arithmetic::evaluate(&ast))
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Check if JSX syntax is valid, without building tokens
 #[wasm_bindgen]
 pub fn is_valid_jsx(input: &str) -> bool {
-    rust_tokenizer(input).is_ok()
+    crate::validate::validate(input).is_ok()
 }
 
 /// Get version information